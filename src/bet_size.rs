@@ -0,0 +1,53 @@
+//! Bet size specification used when building the action tree for a street.
+
+use std::str::FromStr;
+
+/// A single bet/raise size, either relative to the pot or an all-in shove.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BetSize {
+    /// A bet sized as a percentage of the pot (e.g., `50.0` for "50%").
+    PotRelative(f64),
+    /// An all-in shove.
+    AllIn,
+}
+
+impl FromStr for BetSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("allin") {
+            return Ok(BetSize::AllIn);
+        }
+        let pct = s
+            .strip_suffix('%')
+            .ok_or_else(|| format!("invalid bet size: {s}"))?;
+        let value: f64 = pct.parse().map_err(|_| format!("invalid bet size: {s}"))?;
+        Ok(BetSize::PotRelative(value))
+    }
+}
+
+/// The set of bet sizes offered to a single player on a single street.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BetSizeCandidates {
+    pub bet: Vec<BetSize>,
+    pub raise: Vec<BetSize>,
+}
+
+impl TryFrom<(&str, &str)> for BetSizeCandidates {
+    type Error = String;
+
+    fn try_from((bet, raise): (&str, &str)) -> Result<Self, Self::Error> {
+        let parse_list = |s: &str| -> Result<Vec<BetSize>, String> {
+            s.split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(BetSize::from_str)
+                .collect()
+        };
+        Ok(Self {
+            bet: parse_list(bet)?,
+            raise: parse_list(raise)?,
+        })
+    }
+}
@@ -0,0 +1,522 @@
+//! Standalone range-vs-range equity calculation, independent of a built game tree.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{hand_combos, Range, Variant};
+use crate::hand::evaluate_hand;
+
+/// The result of [`compute_equity`]: per-combo and aggregate equity for both players.
+#[derive(Debug, Clone)]
+pub struct EquityResult {
+    /// `(card1, card2)` combos considered for each player, in the same order as
+    /// `per_combo_equity`.
+    pub combos: [Vec<(u8, u8)>; 2],
+    /// Equity of each combo in `combos`, in `[0.0, 1.0]` (win = 1.0, tie split evenly).
+    pub per_combo_equity: [Vec<f32>; 2],
+    /// Range-weighted average equity for each player: each combo contributes in
+    /// proportion to its [`Range::combo_weight`], not uniformly per combo.
+    pub aggregate_equity: [f32; 2],
+    /// Number of runouts actually enumerated/sampled.
+    pub samples: u64,
+}
+
+/// Computes the equity of `ranges[0]` vs `ranges[1]` given `board_mask` (cards already
+/// on the board) and `dead_mask` (additional cards removed from the deck, e.g. folded
+/// hands), independent of any built [`crate::PostFlopGame`] tree.
+///
+/// Runs an exact enumeration over all remaining runouts when `monte_carlo` is `None`.
+/// Otherwise runs a Monte Carlo simulation, sampling runouts until the standard
+/// deviation of the aggregate equity estimate drops to
+/// `monte_carlo.target_stdev`, reporting progress via `monte_carlo.progress` every
+/// `monte_carlo.report_every` samples, and stopping early if
+/// `monte_carlo.cancelled` is set.
+///
+/// Returns `Err` if `board_mask` has more than 5 bits set: unlike [`crate::GameConfig`]'s
+/// `flop`/`turn`/`river` fields, `board_mask` is a raw `u64` with no shape to validate
+/// at construction time, so this is checked here instead of underflowing deep inside
+/// runout enumeration (`missing_board_cards` computes `5 - board.len()`). Fewer than 3
+/// bits (e.g. `0`, a preflop board) is valid: it just means more cards are left to run
+/// out.
+pub fn compute_equity(
+    ranges: &[Range; 2],
+    board_mask: u64,
+    dead_mask: u64,
+    variant: Variant,
+    monte_carlo: Option<MonteCarloParams>,
+) -> Result<EquityResult, String> {
+    let num_board_cards = board_mask.count_ones() as usize;
+    if num_board_cards > 5 {
+        return Err(format!("board_mask must have at most 5 cards set, got {num_board_cards}"));
+    }
+
+    let combos = [
+        hand_combos(&ranges[0], variant, board_mask | dead_mask),
+        hand_combos(&ranges[1], variant, board_mask | dead_mask),
+    ];
+    let weights = [
+        combos[0].iter().map(|&(c1, c2)| ranges[0].combo_weight(c1, c2)).collect(),
+        combos[1].iter().map(|&(c1, c2)| ranges[1].combo_weight(c1, c2)).collect(),
+    ];
+
+    let board: Vec<u8> = (0..52).filter(|&c| board_mask & (1 << c) != 0).collect();
+
+    Ok(match monte_carlo {
+        None => enumerate_equity(&combos, &weights, &board, dead_mask, variant),
+        Some(params) => monte_carlo_equity(&combos, &weights, &board, dead_mask, variant, params),
+    })
+}
+
+/// Tuning knobs for the Monte Carlo equity path.
+pub struct MonteCarloParams<'a> {
+    /// Stop sampling once the aggregate equity estimate's standard deviation drops to
+    /// (or below) this value.
+    pub target_stdev: f32,
+    /// Called with `(samples_so_far, current_aggregate_equity)` every `report_every` samples.
+    pub progress: Option<&'a dyn Fn(u64, [f32; 2])>,
+    /// How many samples between `progress` calls.
+    pub report_every: u64,
+    /// Checked between samples; sampling stops early once set.
+    pub cancelled: &'a AtomicBool,
+    /// Hard cap on the number of samples, in case `target_stdev` is never reached.
+    pub max_samples: u64,
+}
+
+fn missing_board_cards(board: &[u8]) -> usize {
+    5 - board.len()
+}
+
+fn enumerate_equity(
+    combos: &[Vec<(u8, u8)>; 2],
+    weights: &[Vec<f32>; 2],
+    board: &[u8],
+    dead_mask: u64,
+    variant: Variant,
+) -> EquityResult {
+    let mut wins = [vec![0.0f32; combos[0].len()], vec![0.0f32; combos[1].len()]];
+    let mut totals = [vec![0.0f32; combos[0].len()], vec![0.0f32; combos[1].len()]];
+    let mut samples = 0u64;
+
+    // Every (hand0, hand1) pair draws its runouts from the same pool (the deck minus
+    // `board` and `dead_mask`; only the pair's own four hole cards differ), so the
+    // expensive recursive `combinations()` call is generated once here and reused by
+    // filtering out runouts that collide with a given pair's hole cards, instead of
+    // being regenerated from scratch inside the `hand0 x hand1` double loop below.
+    let board_mask = board.iter().fold(0u64, |m, &c| m | (1 << c));
+    let pool: Vec<u8> = (0..52)
+        .filter(|&c| variant.contains_card(c) && (dead_mask | board_mask) & (1 << c) == 0)
+        .collect();
+    let all_runouts = combinations(&pool, missing_board_cards(board));
+
+    for (i, &hand0) in combos[0].iter().enumerate() {
+        for (j, &hand1) in combos[1].iter().enumerate() {
+            if hand0.0 == hand1.0 || hand0.0 == hand1.1 || hand0.1 == hand1.0 || hand0.1 == hand1.1 {
+                continue;
+            }
+            let hole_mask = (1u64 << hand0.0) | (1u64 << hand0.1) | (1u64 << hand1.0) | (1u64 << hand1.1);
+
+            for runout in &all_runouts {
+                if runout.iter().any(|&c| hole_mask & (1 << c) != 0) {
+                    continue;
+                }
+                let (score0, score1) = showdown_score(hand0, hand1, board, runout, variant);
+                totals[0][i] += 1.0;
+                totals[1][j] += 1.0;
+                samples += 1;
+                if score0 > score1 {
+                    wins[0][i] += 1.0;
+                } else if score1 > score0 {
+                    wins[1][j] += 1.0;
+                } else {
+                    wins[0][i] += 0.5;
+                    wins[1][j] += 0.5;
+                }
+            }
+        }
+    }
+
+    finalize_result(combos, weights, wins, totals, samples)
+}
+
+fn monte_carlo_equity(
+    combos: &[Vec<(u8, u8)>; 2],
+    weights: &[Vec<f32>; 2],
+    board: &[u8],
+    dead_mask: u64,
+    variant: Variant,
+    params: MonteCarloParams,
+) -> EquityResult {
+    let mut wins = [vec![0.0f32; combos[0].len()], vec![0.0f32; combos[1].len()]];
+    let mut totals = [vec![0.0f32; combos[0].len()], vec![0.0f32; combos[1].len()]];
+    let mut aggregate_history: Vec<[f32; 2]> = Vec::new();
+    let mut samples = 0u64;
+    let mut attempts = 0u64;
+    let mut rng_state: u64 = 0x9E3779B97F4A7C15;
+
+    // Running per-player weighted sum/weight sum backing `current_aggregate` below,
+    // updated incrementally instead of by calling `aggregate` (which is O(total
+    // combos)) on every single sample; see `update_running_aggregate`'s doc comment.
+    let mut running_weighted_sum = [0.0f32; 2];
+    let mut running_weight_sum = [0.0f32; 2];
+
+    // Bounded by `attempts`, not `samples`: a hand-pair draw that overlaps (see
+    // below) consumes an attempt without ever becoming a sample, and if every
+    // surviving combo pair overlaps (e.g. both ranges pinned to the same single
+    // combo by `dead_mask`/board), `samples` would never advance at all, defeating
+    // `max_samples` as a hard cap.
+    while attempts < params.max_samples {
+        attempts += 1;
+
+        if params.cancelled.load(Ordering::Relaxed) {
+            break;
+        }
+
+        let i = next_random(&mut rng_state) as usize % combos[0].len().max(1);
+        let j = next_random(&mut rng_state) as usize % combos[1].len().max(1);
+        if combos[0].is_empty() || combos[1].is_empty() {
+            break;
+        }
+        let (hand0, hand1) = (combos[0][i], combos[1][j]);
+        if hand0.0 == hand1.0 || hand0.0 == hand1.1 || hand0.1 == hand1.0 || hand0.1 == hand1.1 {
+            continue;
+        }
+
+        let used_mask = dead_mask
+            | (1u64 << hand0.0)
+            | (1u64 << hand0.1)
+            | (1u64 << hand1.0)
+            | (1u64 << hand1.1)
+            | board.iter().fold(0u64, |m, &c| m | (1 << c));
+
+        let runout = random_runout(used_mask, variant, missing_board_cards(board), &mut rng_state);
+        let (score0, score1) = showdown_score(hand0, hand1, board, &runout, variant);
+
+        let old_wins = [wins[0][i], wins[1][j]];
+        let old_totals = [totals[0][i], totals[1][j]];
+
+        totals[0][i] += 1.0;
+        totals[1][j] += 1.0;
+        samples += 1;
+        if score0 > score1 {
+            wins[0][i] += 1.0;
+        } else if score1 > score0 {
+            wins[1][j] += 1.0;
+        } else {
+            wins[0][i] += 0.5;
+            wins[1][j] += 0.5;
+        }
+
+        update_running_aggregate(
+            &mut running_weighted_sum[0],
+            &mut running_weight_sum[0],
+            weights[0][i],
+            old_wins[0],
+            old_totals[0],
+            wins[0][i],
+            totals[0][i],
+        );
+        update_running_aggregate(
+            &mut running_weighted_sum[1],
+            &mut running_weight_sum[1],
+            weights[1][j],
+            old_wins[1],
+            old_totals[1],
+            wins[1][j],
+            totals[1][j],
+        );
+        let current_aggregate = [
+            if running_weight_sum[0] > 0.0 { running_weighted_sum[0] / running_weight_sum[0] } else { 0.0 },
+            if running_weight_sum[1] > 0.0 { running_weighted_sum[1] / running_weight_sum[1] } else { 0.0 },
+        ];
+        aggregate_history.push(current_aggregate);
+
+        if let Some(progress) = params.progress {
+            if samples.is_multiple_of(params.report_every) {
+                progress(samples, current_aggregate);
+            }
+        }
+
+        if samples >= 64 && stdev(&aggregate_history) <= params.target_stdev {
+            break;
+        }
+    }
+
+    finalize_result(combos, weights, wins, totals, samples)
+}
+
+/// Incrementally updates one player's running weighted-sum/weight-sum (together
+/// giving the same range-weighted average equity `aggregate` computes from scratch —
+/// see its doc comment) after a single combo's `wins`/`totals` changed from
+/// `old_wins`/`old_total` to `new_wins`/`new_total`. Only that one combo's
+/// contribution needs to move: this replaces a fresh `aggregate` call (`O(total
+/// combos)`) with `O(1)` work per sample, which is what actually bounds
+/// `monte_carlo_equity`'s per-sample cost now that `samples` can run into the
+/// hundreds of thousands.
+fn update_running_aggregate(
+    weighted_sum: &mut f32,
+    weight_sum: &mut f32,
+    weight: f32,
+    old_wins: f32,
+    old_total: f32,
+    new_wins: f32,
+    new_total: f32,
+) {
+    if old_total > 0.0 {
+        *weighted_sum -= (old_wins / old_total) * weight;
+    } else {
+        // This combo had no samples yet, so `aggregate` wouldn't have counted its
+        // weight in the denominator either; now that it has one, it joins the pool.
+        *weight_sum += weight;
+    }
+    *weighted_sum += (new_wins / new_total) * weight;
+}
+
+fn stdev(history: &[[f32; 2]]) -> f32 {
+    let n = history.len().clamp(1, 256);
+    let recent = &history[history.len() - n..];
+    let mean: f32 = recent.iter().map(|a| a[0]).sum::<f32>() / n as f32;
+    let variance: f32 = recent.iter().map(|a| (a[0] - mean).powi(2)).sum::<f32>() / n as f32;
+    variance.sqrt()
+}
+
+/// Range-weighted average equity: each combo's win rate (`wins[idx] / totals[idx]`)
+/// contributes to the average in proportion to `weights[idx]` (see
+/// [`Range::combo_weight`]), so a combo that only appears in the range some fraction of
+/// the time counts for that same fraction here, rather than being averaged in as if it
+/// were a full-weight combo.
+fn aggregate(
+    combos: &[Vec<(u8, u8)>; 2],
+    weights: &[Vec<f32>; 2],
+    wins: &[Vec<f32>; 2],
+    totals: &[Vec<f32>; 2],
+) -> [f32; 2] {
+    let mut result = [0.0; 2];
+    for player in 0..2 {
+        let mut sum = 0.0;
+        let mut weight_sum = 0.0;
+        for idx in 0..combos[player].len() {
+            if totals[player][idx] > 0.0 {
+                let combo_equity = wins[player][idx] / totals[player][idx];
+                sum += combo_equity * weights[player][idx];
+                weight_sum += weights[player][idx];
+            }
+        }
+        result[player] = if weight_sum > 0.0 { sum / weight_sum } else { 0.0 };
+    }
+    result
+}
+
+fn finalize_result(
+    combos: &[Vec<(u8, u8)>; 2],
+    weights: &[Vec<f32>; 2],
+    wins: [Vec<f32>; 2],
+    totals: [Vec<f32>; 2],
+    samples: u64,
+) -> EquityResult {
+    let per_combo_equity = [
+        wins[0]
+            .iter()
+            .zip(totals[0].iter())
+            .map(|(&w, &t)| if t > 0.0 { w / t } else { 0.0 })
+            .collect(),
+        wins[1]
+            .iter()
+            .zip(totals[1].iter())
+            .map(|(&w, &t)| if t > 0.0 { w / t } else { 0.0 })
+            .collect(),
+    ];
+    let aggregate_equity = aggregate(combos, weights, &wins, &totals);
+
+    EquityResult {
+        combos: combos.clone(),
+        per_combo_equity,
+        aggregate_equity,
+        samples,
+    }
+}
+
+fn combinations(cards: &[u8], k: usize) -> Vec<Vec<u8>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    let mut result = Vec::new();
+    for i in 0..cards.len() {
+        for mut tail in combinations(&cards[i + 1..], k - 1) {
+            tail.insert(0, cards[i]);
+            result.push(tail);
+        }
+    }
+    result
+}
+
+fn next_random(state: &mut u64) -> u64 {
+    // xorshift64*
+    *state ^= *state >> 12;
+    *state ^= *state << 25;
+    *state ^= *state >> 27;
+    state.wrapping_mul(0x2545F4914F6CDD1D)
+}
+
+fn random_runout(used_mask: u64, variant: Variant, count: usize, rng_state: &mut u64) -> Vec<u8> {
+    let mut available: Vec<u8> = (0..52)
+        .filter(|&c| variant.contains_card(c) && used_mask & (1 << c) == 0)
+        .collect();
+    let mut runout = Vec::with_capacity(count);
+    for _ in 0..count {
+        if available.is_empty() {
+            break;
+        }
+        let index = next_random(rng_state) as usize % available.len();
+        runout.push(available.swap_remove(index));
+    }
+    runout
+}
+
+fn showdown_score(
+    hand0: (u8, u8),
+    hand1: (u8, u8),
+    board: &[u8],
+    runout: &[u8],
+    variant: Variant,
+) -> (i64, i64) {
+    let mut cards0 = vec![hand0.0, hand0.1];
+    let mut cards1 = vec![hand1.0, hand1.1];
+    cards0.extend_from_slice(board);
+    cards0.extend_from_slice(runout);
+    cards1.extend_from_slice(board);
+    cards1.extend_from_slice(runout);
+
+    // See `crate::hand`'s module doc comment: this crate only ever had one hand
+    // evaluator, so `variant` is threaded through it unconditionally.
+    let rank0 = evaluate_hand(&cards0, variant);
+    let rank1 = evaluate_hand(&cards1, variant);
+    match rank0.cmp_for_variant(&rank1, variant) {
+        std::cmp::Ordering::Greater => (1, 0),
+        std::cmp::Ordering::Less => (0, 1),
+        std::cmp::Ordering::Equal => (0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_from_str;
+
+    fn card(s: &str) -> u8 {
+        card_from_str(s).unwrap()
+    }
+
+    fn mask(cards: &[&str]) -> u64 {
+        cards.iter().fold(0u64, |m, &s| m | (1 << card(s)))
+    }
+
+    /// AA vs KK is a well-known preflop favorite for the aces; on a blank turn board
+    /// (one card left to come, no help for the kings) the aces' equity climbs well
+    /// above the ~82% preflop figure.
+    #[test]
+    fn aa_vs_kk_known_equity() {
+        let ranges = ["AA".parse().unwrap(), "KK".parse().unwrap()];
+        let board_mask = mask(&["2c", "7d", "9h", "Jd"]);
+        let result = compute_equity(&ranges, board_mask, 0, Variant::Standard, None).unwrap();
+        assert_eq!(result.combos[0].len(), 6);
+        assert_eq!(result.combos[1].len(), 6);
+        assert!(result.aggregate_equity[0] > 0.9 && result.aggregate_equity[0] < 0.99);
+        assert!((result.aggregate_equity[0] + result.aggregate_equity[1] - 1.0).abs() < 1e-4);
+    }
+
+    /// A made hand on the river (no cards left to come) always has equity 0 or 1 (or
+    /// 0.5 on a chop) against a single combo, never something in between.
+    #[test]
+    fn river_equity_is_decisive() {
+        let ranges = ["AA".parse().unwrap(), "KK".parse().unwrap()];
+        let board_mask = mask(&["2c", "7d", "9h", "Jd", "4s"]);
+        let result = compute_equity(&ranges, board_mask, 0, Variant::Standard, None).unwrap();
+        assert_eq!(result.samples, (result.combos[0].len() * result.combos[1].len()) as u64);
+        assert_eq!(result.aggregate_equity[0], 1.0);
+        assert_eq!(result.aggregate_equity[1], 0.0);
+    }
+
+    /// A combo held at a lower range weight contributes proportionally less to the
+    /// range-weighted average than a full-weight combo, rather than being averaged in
+    /// as if every combo counted equally (see `aggregate`'s doc comment).
+    #[test]
+    fn aggregate_equity_respects_combo_weights() {
+        let mut range0 = Range::new();
+        range0.set_pair_weight(7, 1.0); // "99": the board pairs two of them, so the one
+                                         // remaining combo (9h9s) makes quads and always wins.
+        range0.set_pair_weight(1, 0.3); // "33": down-weighted, and always loses (just a
+                                         // middle pair against the fixed "AA" below).
+        let ranges = [range0, "AA".parse().unwrap()];
+
+        let board_mask = mask(&["9c", "9d", "2h", "5s", "7c"]);
+        let result = compute_equity(&ranges, board_mask, 0, Variant::Standard, None).unwrap();
+
+        // One "99" combo (quads, equity 1.0) plus six "33" combos (equity 0.0 each).
+        assert_eq!(result.combos[0].len(), 7);
+
+        let weighted = 1.0 / (1.0 + 6.0 * 0.3); // sum(equity * weight) / sum(weight)
+        let unweighted = 1.0 / 7.0; // what a uniform per-combo average would give instead
+        assert!((result.aggregate_equity[0] - weighted).abs() < 1e-4);
+        assert!((result.aggregate_equity[0] - unweighted).abs() > 0.1);
+    }
+
+    /// A `board_mask` with more than 5 bits set used to underflow inside
+    /// `missing_board_cards` (`5 - board.len()`) and panic deep inside runout
+    /// enumeration instead of being rejected up front.
+    #[test]
+    fn rejects_a_board_mask_with_too_many_cards() {
+        let ranges = ["AA".parse().unwrap(), "KK".parse().unwrap()];
+        let board_mask = mask(&["2c", "7d", "9h", "Jd", "4s", "8c"]);
+        assert!(compute_equity(&ranges, board_mask, 0, Variant::Standard, None).is_err());
+    }
+
+    /// `monte_carlo_equity` tracks each player's weighted-sum/weight-sum incrementally
+    /// (see `update_running_aggregate`) instead of recomputing `aggregate` from scratch
+    /// every sample; this combo-weighted range (mirroring
+    /// `aggregate_equity_respects_combo_weights`) would catch the tracker double-counting
+    /// or dropping a combo's weight as samples land on it repeatedly.
+    #[test]
+    fn monte_carlo_aggregate_respects_combo_weights() {
+        let mut range0 = Range::new();
+        range0.set_pair_weight(7, 1.0); // "99": the board pairs two of them, so the one
+                                         // remaining combo (9h9s) makes quads and always wins.
+        range0.set_pair_weight(1, 0.3); // "33": down-weighted, and always loses (just a
+                                         // middle pair against the fixed "AA" below).
+        let ranges = [range0, "AA".parse().unwrap()];
+
+        let board_mask = mask(&["9c", "9d", "2h", "5s"]); // turn card left to come: forces Monte Carlo
+        let cancelled = AtomicBool::new(false);
+        let params = MonteCarloParams {
+            target_stdev: 0.0, // unreachable, so every run uses the full max_samples budget
+            progress: None,
+            report_every: 1,
+            cancelled: &cancelled,
+            max_samples: 2000,
+        };
+        let result = compute_equity(&ranges, board_mask, 0, Variant::Standard, Some(params)).unwrap();
+
+        let weighted = 1.0 / (1.0 + 6.0 * 0.3);
+        let unweighted = 1.0 / 7.0;
+        assert!((result.aggregate_equity[0] - weighted).abs() < 0.05);
+        assert!((result.aggregate_equity[0] - unweighted).abs() > 0.1);
+    }
+
+    /// If both ranges collapse (via `dead_mask`) to the exact same single combo, every
+    /// sampled hand pair shares both hole cards and gets skipped by the overlap check,
+    /// so `samples` never advances; `max_samples` must still bound the number of
+    /// attempts, or this spins forever instead of returning zero samples.
+    #[test]
+    fn monte_carlo_respects_max_samples_when_every_pair_overlaps() {
+        let ranges = ["AA".parse().unwrap(), "AA".parse().unwrap()];
+        let dead_mask = mask(&["Ac", "As"]); // leaves exactly one "AA" combo: Ah/Ad, shared by both ranges
+        let cancelled = AtomicBool::new(false);
+        let params = MonteCarloParams {
+            target_stdev: 0.0, // unreachable with zero samples, so max_samples must fire
+            progress: None,
+            report_every: 1,
+            cancelled: &cancelled,
+            max_samples: 1000,
+        };
+        let result = compute_equity(&ranges, 0, dead_mask, Variant::Standard, Some(params)).unwrap();
+        assert_eq!(result.samples, 0);
+    }
+}
@@ -0,0 +1,1176 @@
+//! Game tree construction and the [`PostFlopGame`] type.
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::ptr::NonNull;
+use std::rc::Rc;
+
+use crate::storage::MmapArena;
+use crate::{Action, BetSize, BetSizeCandidates, MutexLike, Player, Range, Variant, NOT_DEALT};
+
+/// Which backend holds the per-node regret/strategy arrays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StorageMode {
+    /// Full-precision arrays, one big in-RAM allocation.
+    #[default]
+    Uncompressed,
+    /// 16-bit compressed arrays, one big in-RAM allocation.
+    Compressed,
+    /// Arrays backed by a memory-mapped file, so the OS can page cold subtrees to
+    /// disk instead of the solve running out of RAM on large trees. See
+    /// [`PostFlopGame::allocate_memory_mmap`].
+    MemoryMapped,
+}
+
+/// Backing storage for one node's flat regret/strategy-sum array.
+///
+/// Every node starts out `Owned`, holding its own `Vec<f32>` exactly as before this
+/// type existed. [`PostFlopGame::allocate_memory_mmap`] then walks the built tree and
+/// swaps each node's storage for an `Mmap` window: a non-overlapping slice of a single
+/// [`crate::storage::MmapArena`] shared by the whole tree. Both variants expose the
+/// same `&[f32]`/`&mut [f32]` surface via `Deref`/`DerefMut`, so [`crate::solve_step`]
+/// and the rest of the solver read/write through either backend identically.
+pub(crate) enum NodeStorage {
+    Owned(Vec<f32>),
+    Mmap { ptr: NonNull<f32>, len: usize },
+}
+
+// Safety: an `Mmap` window points into an `MmapArena`'s mapped memory, which is
+// itself `Send`/`Sync` (see `crate::storage`). As with the rest of `MutexLike<T>`
+// (see its module doc comment), avoiding concurrent aliasing of the same window is
+// the caller's responsibility, not enforced here.
+unsafe impl Send for NodeStorage {}
+unsafe impl Sync for NodeStorage {}
+
+impl std::fmt::Debug for NodeStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl Clone for NodeStorage {
+    /// Always clones into a fresh `Owned` copy: an `Mmap` window has no independent
+    /// lifetime to share, and the only place a `Node` gets cloned is
+    /// [`PostFlopGame::node_at_mut`]'s `Rc::make_mut`, which needs a copy it can
+    /// mutate without aliasing the original window.
+    fn clone(&self) -> Self {
+        Self::Owned(self.to_vec())
+    }
+}
+
+impl std::ops::Deref for NodeStorage {
+    type Target = [f32];
+    fn deref(&self) -> &[f32] {
+        match self {
+            Self::Owned(v) => v,
+            Self::Mmap { ptr, len } => unsafe { std::slice::from_raw_parts(ptr.as_ptr(), *len) },
+        }
+    }
+}
+
+impl std::ops::DerefMut for NodeStorage {
+    fn deref_mut(&mut self) -> &mut [f32] {
+        match self {
+            Self::Owned(v) => v,
+            Self::Mmap { ptr, len } => unsafe { std::slice::from_raw_parts_mut(ptr.as_ptr(), *len) },
+        }
+    }
+}
+
+/// Full specification of a postflop spot: board, stacks, ranges and bet sizes.
+#[derive(Debug, Clone)]
+pub struct GameConfig {
+    /// The deck/hand-ranking variant to build the tree for.
+    ///
+    /// Defaults to [`Variant::Standard`]; set to [`Variant::ShortDeck`] to build a
+    /// 36-card (6-plus) Hold'em tree instead.
+    pub variant: Variant,
+    pub flop: [u8; 3],
+    pub turn: u8,
+    pub river: u8,
+    pub starting_pot: i32,
+    pub effective_stack: i32,
+    pub range: [Range; 2],
+    pub flop_bet_sizes: [BetSizeCandidates; 2],
+    pub turn_bet_sizes: [BetSizeCandidates; 2],
+    pub river_bet_sizes: [BetSizeCandidates; 2],
+    pub add_all_in_threshold: f64,
+    pub force_all_in_threshold: f64,
+    pub adjust_last_two_bet_sizes: bool,
+    /// Collapse turn/river chance nodes that are equivalent under the remaining suit
+    /// symmetry of the board and both ranges into a single stored subtree.
+    ///
+    /// Defaults to `false`. See [`canonical_chance_card`].
+    ///
+    /// Currently saves nothing on any tree this crate can actually solve: merging
+    /// only ever runs on the chance-dealing path in [`build_tree`], and every node
+    /// that path builds is a [`Node::placeholder_terminal`] (see `build_tree`'s doc
+    /// comment) — the real, per-node-array decision trees [`build_betting_round`]
+    /// builds only exist for configs whose board was already fully dealt before
+    /// `with_config` ran, which never passes through chance-dealing or merging at
+    /// all. [`PostFlopGame::memory_usage`]'s reduction when this is enabled is real
+    /// (it does dedupe placeholder terminals), but it's a reduction in empty-struct
+    /// count, not in the costly regret/strategy storage this option was meant to cut
+    /// on large trees (e.g. Button-vs-BB with many streets left to deal). Wiring real,
+    /// path-dependent decision-node construction through the chance-dealing path —
+    /// so there's something this flag can actually multiply-fold the storage of — is
+    /// a larger change than this option makes on its own.
+    pub merge_isomorphic_chances: bool,
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self {
+            variant: Variant::Standard,
+            flop: [NOT_DEALT; 3],
+            turn: NOT_DEALT,
+            river: NOT_DEALT,
+            starting_pot: 0,
+            effective_stack: 0,
+            range: [Range::default(), Range::default()],
+            flop_bet_sizes: Default::default(),
+            turn_bet_sizes: Default::default(),
+            river_bet_sizes: Default::default(),
+            add_all_in_threshold: 0.0,
+            force_all_in_threshold: 0.0,
+            adjust_last_two_bet_sizes: false,
+            merge_isomorphic_chances: false,
+        }
+    }
+}
+
+/// The pot, each player's total contribution, and (for a fold) who wins it, for a
+/// terminal node. `fold_winner` is `None` at a showdown terminal (see
+/// [`Node::showdown`]).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TerminalPayoff {
+    pub pot: i32,
+    pub committed: [i32; 2],
+    pub fold_winner: Option<usize>,
+}
+
+/// A precomputed OOP-combo-vs-IP-combo showdown comparison table for one fully-dealt
+/// board, shared (via `Rc`) across every showdown terminal in that board's subtree so
+/// it's only built once per board instead of once per terminal node.
+#[derive(Debug)]
+pub(crate) struct ShowdownTable {
+    /// `comparisons[oop_hand][ip_hand]`: `None` if the two combos share a card (so
+    /// they can never actually occur together), otherwise how the OOP combo's hand
+    /// compares to the IP combo's hand.
+    comparisons: Vec<Vec<Option<std::cmp::Ordering>>>,
+}
+
+impl ShowdownTable {
+    fn build(oop: &[(u8, u8)], ip: &[(u8, u8)], board: &[u8], variant: Variant) -> Self {
+        let comparisons = oop
+            .iter()
+            .map(|&(a, b)| {
+                ip.iter()
+                    .map(|&(c, d)| {
+                        if a == c || a == d || b == c || b == d {
+                            return None;
+                        }
+                        let mut cards_oop = vec![a, b];
+                        cards_oop.extend_from_slice(board);
+                        let mut cards_ip = vec![c, d];
+                        cards_ip.extend_from_slice(board);
+                        let rank_oop = crate::hand::evaluate_hand(&cards_oop, variant);
+                        let rank_ip = crate::hand::evaluate_hand(&cards_ip, variant);
+                        Some(rank_oop.cmp_for_variant(&rank_ip, variant))
+                    })
+                    .collect()
+            })
+            .collect();
+        Self { comparisons }
+    }
+
+    /// Returns how the OOP combo at `oop_hand` compares to the IP combo at `ip_hand`
+    /// (indices into the node's `private_hand_cards`), or `None` if they share a card.
+    pub(crate) fn compare(&self, oop_hand: usize, ip_hand: usize) -> Option<std::cmp::Ordering> {
+        self.comparisons[oop_hand][ip_hand]
+    }
+
+    /// Returns `hand`'s (at index `hand`, for the player indicated by `hand_is_oop`)
+    /// raw showdown equity against the opponent's whole range, weighted by
+    /// `reach_opp` (one weight per opponent combo, in `compare`'s other index order):
+    /// the reach-weighted fraction of comparisons `hand` wins, counting a tie as half
+    /// a win. Combos that share a card with `hand` are skipped, exactly as in
+    /// [`crate::equity::compute_equity`]'s combo filtering. `0.0` if every opponent
+    /// combo is skipped (no weight, or every one shares a card).
+    pub(crate) fn win_fraction(&self, hand: usize, hand_is_oop: bool, reach_opp: &[f32]) -> f32 {
+        let mut win = 0.0f32;
+        let mut total = 0.0f32;
+        for (j, &reach) in reach_opp.iter().enumerate() {
+            if reach == 0.0 {
+                continue;
+            }
+            let (oop_hand, ip_hand) = if hand_is_oop { (hand, j) } else { (j, hand) };
+            let Some(ordering) = self.compare(oop_hand, ip_hand) else {
+                continue;
+            };
+            let ordering = if hand_is_oop { ordering } else { ordering.reverse() };
+            let outcome = match ordering {
+                std::cmp::Ordering::Greater => 1.0,
+                std::cmp::Ordering::Less => 0.0,
+                std::cmp::Ordering::Equal => 0.5,
+            };
+            win += reach * outcome;
+            total += reach;
+        }
+        if total > 0.0 {
+            win / total
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A single node in the game tree: either a decision node (one player acts), a
+/// chance node (a card is dealt), or a terminal node (fold/showdown).
+#[derive(Debug, Clone)]
+pub struct Node {
+    pub player: Player,
+    pub actions: Vec<Action>,
+    /// Children are reference-counted rather than owned outright so that isomorphic
+    /// chance nodes (see [`GameConfig::merge_isomorphic_chances`]) can share a single
+    /// stored subtree instead of each duplicating it.
+    pub children: Vec<Rc<Node>>,
+    /// When set, this node's action frequencies are pinned to these values rather
+    /// than being produced by regret matching. Set via
+    /// [`PostFlopGame::lock_node_strategy`]. One entry per hand per action, in the
+    /// same order as `actions`.
+    pub locked_strategy: Option<Vec<f32>>,
+    /// Pot/commitment/winner info for a terminal node; `None` for decision and chance
+    /// nodes, and also for the still-placeholder terminal produced when a street is
+    /// left undealt (see [`build_tree`]'s doc comment).
+    pub(crate) terminal: Option<TerminalPayoff>,
+    /// The showdown comparison table shared by every terminal below one fully-dealt
+    /// board, used both to resolve showdowns and (via `compare` returning `None`) to
+    /// skip hand combos that share a card with the opponent's — including at a fold
+    /// terminal, where the comparison result itself is irrelevant but the overlap
+    /// check still is. `None` only for the placeholder terminal of an undealt street.
+    pub(crate) showdown: Option<Rc<ShowdownTable>>,
+    /// Per-(hand, action) accumulated regrets, backing this node's regret-matching
+    /// strategy. Empty for non-decision nodes. See [`crate::solver`].
+    pub(crate) regrets: MutexLike<NodeStorage>,
+    /// Per-(hand, action) accumulated strategy weight across iterations, backing this
+    /// node's time-averaged strategy. Empty for non-decision nodes.
+    pub(crate) strategy_sum: MutexLike<NodeStorage>,
+    /// `[cfv_oop, cfv_ip]`: each player's per-hand counterfactual value at this node,
+    /// as of the last time [`crate::finalize`] evaluated the tree under both players'
+    /// current [`Node::average_strategy`]. Empty until then. Read by
+    /// [`crate::Interpreter::expected_values`].
+    pub(crate) cfv: MutexLike<[Vec<f32>; 2]>,
+}
+
+impl Node {
+    fn placeholder_terminal() -> Self {
+        Self {
+            player: Player::Terminal,
+            actions: Vec::new(),
+            children: Vec::new(),
+            locked_strategy: None,
+            terminal: None,
+            showdown: None,
+            regrets: MutexLike::new(NodeStorage::Owned(Vec::new())),
+            strategy_sum: MutexLike::new(NodeStorage::Owned(Vec::new())),
+            cfv: MutexLike::new([Vec::new(), Vec::new()]),
+        }
+    }
+
+    fn fold_terminal(folder: usize, pot: i32, committed: [i32; 2], showdown: Rc<ShowdownTable>) -> Self {
+        Self {
+            player: Player::Terminal,
+            actions: Vec::new(),
+            children: Vec::new(),
+            locked_strategy: None,
+            terminal: Some(TerminalPayoff { pot, committed, fold_winner: Some(1 - folder) }),
+            // A fold's outcome doesn't depend on hand strength, but per-combo
+            // counterfactual value computation still needs the overlap check this
+            // table provides (see `Node::showdown`'s doc comment).
+            showdown: Some(showdown),
+            regrets: MutexLike::new(NodeStorage::Owned(Vec::new())),
+            strategy_sum: MutexLike::new(NodeStorage::Owned(Vec::new())),
+            cfv: MutexLike::new([Vec::new(), Vec::new()]),
+        }
+    }
+
+    fn showdown_terminal(pot: i32, committed: [i32; 2], showdown: Rc<ShowdownTable>) -> Self {
+        Self {
+            player: Player::Terminal,
+            actions: Vec::new(),
+            children: Vec::new(),
+            locked_strategy: None,
+            terminal: Some(TerminalPayoff { pot, committed, fold_winner: None }),
+            showdown: Some(showdown),
+            regrets: MutexLike::new(NodeStorage::Owned(Vec::new())),
+            strategy_sum: MutexLike::new(NodeStorage::Owned(Vec::new())),
+            cfv: MutexLike::new([Vec::new(), Vec::new()]),
+        }
+    }
+
+    fn chance(actions: Vec<Action>, children: Vec<Rc<Node>>) -> Self {
+        Self {
+            player: Player::Chance,
+            actions,
+            children,
+            locked_strategy: None,
+            terminal: None,
+            showdown: None,
+            regrets: MutexLike::new(NodeStorage::Owned(Vec::new())),
+            strategy_sum: MutexLike::new(NodeStorage::Owned(Vec::new())),
+            cfv: MutexLike::new([Vec::new(), Vec::new()]),
+        }
+    }
+
+    fn decision(
+        player: Player,
+        actions: Vec<Action>,
+        children: Vec<Rc<Node>>,
+        num_hands: usize,
+        showdown: Rc<ShowdownTable>,
+    ) -> Self {
+        let len = num_hands * actions.len();
+        Self {
+            player,
+            actions,
+            children,
+            locked_strategy: None,
+            terminal: None,
+            // Kept so that [`crate::Interpreter::equity`] can evaluate raw showdown
+            // equity at a decision node without re-deriving it from a descendant
+            // terminal.
+            showdown: Some(showdown),
+            regrets: MutexLike::new(NodeStorage::Owned(vec![0.0; len])),
+            strategy_sum: MutexLike::new(NodeStorage::Owned(vec![0.0; len])),
+            cfv: MutexLike::new([Vec::new(), Vec::new()]),
+        }
+    }
+
+    /// Returns this node's showdown comparison table, if it has one. `None` for a
+    /// chance node or for the placeholder terminal of an undealt street; `Some` for
+    /// every decision node and every real terminal node below a fully-dealt board.
+    pub(crate) fn showdown_table(&self) -> Option<&Rc<ShowdownTable>> {
+        self.showdown.as_ref()
+    }
+
+    /// Number of hands this node's regret/strategy-sum arrays are laid out for, i.e.
+    /// `self.regrets.lock().len() / self.actions.len()`. `0` for non-decision nodes.
+    pub(crate) fn num_hands(&self) -> usize {
+        if self.actions.is_empty() {
+            0
+        } else {
+            self.regrets.lock().len() / self.actions.len()
+        }
+    }
+
+    /// Returns this decision node's current regret-matching strategy (or its locked
+    /// strategy, if set): one probability per hand per action, in `self.actions` order.
+    /// Called by [`crate::solve_step`] on every unlocked decision node each iteration.
+    pub(crate) fn regret_matching_strategy(&self) -> Vec<f32> {
+        if let Some(locked) = &self.locked_strategy {
+            return locked.clone();
+        }
+        let num_hands = self.num_hands();
+        let num_actions = self.actions.len();
+        let regrets = self.regrets.lock();
+        let mut strategy = vec![0.0f32; num_hands * num_actions];
+        for h in 0..num_hands {
+            let row = &regrets[h * num_actions..(h + 1) * num_actions];
+            let positive_sum: f32 = row.iter().map(|&r| r.max(0.0)).sum();
+            if positive_sum > 0.0 {
+                for a in 0..num_actions {
+                    strategy[h * num_actions + a] = row[a].max(0.0) / positive_sum;
+                }
+            } else {
+                let uniform = 1.0 / num_actions as f32;
+                strategy[h * num_actions..(h + 1) * num_actions].fill(uniform);
+            }
+        }
+        strategy
+    }
+
+    /// Returns this decision node's time-averaged strategy (or its locked strategy, if
+    /// set): the strategy that [`crate::solve`] converges the tree's play toward. Read
+    /// by [`crate::compute_exploitability`]'s best-response pass and by
+    /// [`crate::Interpreter::action_frequencies`].
+    pub(crate) fn average_strategy(&self) -> Vec<f32> {
+        if let Some(locked) = &self.locked_strategy {
+            return locked.clone();
+        }
+        let num_hands = self.num_hands();
+        let num_actions = self.actions.len();
+        let sums = self.strategy_sum.lock();
+        let mut strategy = vec![0.0f32; num_hands * num_actions];
+        for h in 0..num_hands {
+            let row = &sums[h * num_actions..(h + 1) * num_actions];
+            let total: f32 = row.iter().sum();
+            if total > 0.0 {
+                for a in 0..num_actions {
+                    strategy[h * num_actions + a] = row[a] / total;
+                }
+            } else {
+                let uniform = 1.0 / num_actions as f32;
+                strategy[h * num_actions..(h + 1) * num_actions].fill(uniform);
+            }
+        }
+        strategy
+    }
+}
+
+/// A postflop Hold'em game tree, built from a [`GameConfig`].
+pub struct PostFlopGame {
+    config: GameConfig,
+    root: Node,
+    memory_allocated: bool,
+    storage_mode: StorageMode,
+    mmap_arena: Option<MmapArena>,
+}
+
+impl PostFlopGame {
+    /// Builds a game tree from `config`.
+    ///
+    /// Returns an error if the config is inconsistent, e.g. if a supplied range
+    /// contains combos whose ranks don't exist in `config.variant`'s deck (see
+    /// [`Range::is_compatible_with`]), or if the board contains a duplicate card.
+    pub fn with_config(config: &GameConfig) -> Result<Self, String> {
+        for player_range in &config.range {
+            if !player_range.is_compatible_with(config.variant) {
+                return Err(format!(
+                    "a supplied range contains ranks that are not part of the {:?} deck",
+                    config.variant
+                ));
+            }
+        }
+
+        let board: Vec<u8> = config
+            .flop
+            .iter()
+            .chain([&config.turn, &config.river])
+            .copied()
+            .filter(|&c| c != NOT_DEALT)
+            .collect();
+        for &card in &board {
+            if !config.variant.contains_card(card) {
+                return Err(format!(
+                    "board card {card} is not part of the {:?} deck",
+                    config.variant
+                ));
+            }
+        }
+        if board.iter().collect::<std::collections::HashSet<_>>().len() != board.len() {
+            return Err("duplicate card on board".to_string());
+        }
+
+        let root = build_tree(config, &board);
+
+        Ok(Self {
+            config: config.clone(),
+            root,
+            memory_allocated: false,
+            storage_mode: StorageMode::default(),
+            mmap_arena: None,
+        })
+    }
+
+    /// Returns the config this game was built from.
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Returns the root of the game tree.
+    pub fn root(&self) -> &Node {
+        &self.root
+    }
+
+    /// Pins the action frequencies at the decision node reached by following `path`
+    /// (a sequence of child indices from the root) to `frequencies`.
+    ///
+    /// Subsequent [`crate::solve_step`] calls will skip the regret-matching update at
+    /// this node and always emit `frequencies`, while still solving the rest of the
+    /// tree (including this node's own children, and every other node) normally
+    /// against it. `frequencies` must have one entry per hand per available action,
+    /// matching the node's `actions` order.
+    ///
+    /// Returns an error if `path` does not lead to a decision node, or if
+    /// `frequencies`'s length isn't a multiple of the node's action count.
+    ///
+    /// Note: [`build_tree`] only produces decision nodes once the board is fully dealt
+    /// up front (see its doc comment); for a config with any undealt street, `build_tree`
+    /// still only produces chance and terminal nodes, so no `path` can reach a decision
+    /// node for such a config.
+    pub fn lock_node_strategy(&mut self, path: &[usize], frequencies: Vec<f32>) -> Result<(), String> {
+        let node = Self::node_at_mut(&mut self.root, path)?;
+        if node.player != Player::Oop && node.player != Player::Ip {
+            return Err("can only lock the strategy at a decision node".to_string());
+        }
+        if node.actions.is_empty() || !frequencies.len().is_multiple_of(node.actions.len()) {
+            return Err("frequencies length must be a multiple of the node's action count".to_string());
+        }
+        node.locked_strategy = Some(frequencies);
+        Ok(())
+    }
+
+    /// Removes a previously set lock at the node reached by `path`, if any.
+    pub fn unlock_node_strategy(&mut self, path: &[usize]) -> Result<(), String> {
+        let node = Self::node_at_mut(&mut self.root, path)?;
+        node.locked_strategy = None;
+        Ok(())
+    }
+
+    fn node_at_mut<'a>(root: &'a mut Node, path: &[usize]) -> Result<&'a mut Node, String> {
+        let mut node = root;
+        for &index in path {
+            let child = node
+                .children
+                .get_mut(index)
+                .ok_or_else(|| format!("no child at index {index}"))?;
+            // A locked node may live inside a subtree shared by suit-isomorphic chance
+            // nodes (see `GameConfig::merge_isomorphic_chances`); `make_mut` gives this
+            // lock its own copy instead of pinning every card that shares the subtree.
+            node = Rc::make_mut(child);
+        }
+        Ok(node)
+    }
+
+    /// Returns the (card1, card2) private hand combos for `player` (0 = OOP, 1 = IP)
+    /// that are reachable given the board and the player's range.
+    ///
+    /// Respects [`GameConfig::variant`]: in short-deck mode, no combo referencing a
+    /// rank outside the 36-card deck is ever produced.
+    pub fn private_hand_cards(&self, player: usize) -> Vec<(u8, u8)> {
+        let board_mask = self.board_mask();
+        crate::range::hand_combos(&self.config.range[player], self.config.variant, board_mask)
+    }
+
+    /// Returns the range weight of each combo returned by [`Self::private_hand_cards`]
+    /// for `player`, in the same order.
+    pub fn hand_weights(&self, player: usize) -> Vec<f32> {
+        self.private_hand_cards(player)
+            .into_iter()
+            .map(|(c1, c2)| self.config.range[player].combo_weight(c1, c2))
+            .collect()
+    }
+
+    fn board_mask(&self) -> u64 {
+        [self.config.flop[0], self.config.flop[1], self.config.flop[2], self.config.turn, self.config.river]
+            .into_iter()
+            .filter(|&c| c != NOT_DEALT)
+            .fold(0u64, |mask, c| mask | (1 << c))
+    }
+
+    /// Returns `(uncompressed_bytes, compressed_bytes)` estimated memory usage for solving.
+    pub fn memory_usage(&self) -> (u64, u64) {
+        let num_nodes = count_nodes(&self.root) as u64;
+        let hands = self.private_hand_cards(0).len().max(self.private_hand_cards(1).len()) as u64;
+        let per_node = hands * 4;
+        (per_node * num_nodes * 4, per_node * num_nodes)
+    }
+
+    /// Returns the largest absolute regret value stored anywhere in the tree, as a
+    /// cheap, real diagnostic for how far solving has progressed (a converged solve
+    /// tends to drive this down; it does not by itself bound exploitability the way
+    /// [`crate::compute_exploitability`] does).
+    pub fn max_abs_regret(&self) -> f32 {
+        fn walk(node: &Node) -> f32 {
+            let here = crate::sliceop::max_abs_slice(&node.regrets.lock());
+            node.children.iter().fold(here, |acc, child| acc.max(walk(child)))
+        }
+        walk(&self.root)
+    }
+
+    /// Allocates the strategy/regret storage needed to solve this game as a single
+    /// in-RAM allocation.
+    ///
+    /// `compress` selects the compressed (16-bit) or uncompressed (32-bit) storage layout.
+    ///
+    /// Note: decision nodes already carry their own regret/strategy storage (see
+    /// [`Node::regrets`]/[`Node::strategy_sum`]) as soon as the tree is built; this
+    /// method only records which backend callers intend to use, for
+    /// [`Self::storage_mode`] and [`Self::memory_usage`] to report, and — if the game
+    /// was previously switched to [`StorageMode::MemoryMapped`] — copies every node's
+    /// storage back out of the arena into its own `Vec` before dropping it, so no
+    /// node is left pointing at unmapped memory. The `Compressed` backend doesn't yet
+    /// actually compress that storage (it's recorded the same way as `Uncompressed`),
+    /// so it's currently a label rather than a memory-usage change.
+    pub fn allocate_memory(&mut self, compress: bool) {
+        self.storage_mode = if compress {
+            StorageMode::Compressed
+        } else {
+            StorageMode::Uncompressed
+        };
+        if self.mmap_arena.is_some() {
+            Self::restore_owned_storage(&self.root, &mut HashSet::new());
+            self.mmap_arena = None;
+        }
+        self.memory_allocated = true;
+    }
+
+    /// Allocates the strategy/regret storage needed to solve this game as a
+    /// memory-mapped file at `path`, instead of one large in-RAM allocation.
+    ///
+    /// Large trees can otherwise fail partway through solving once RAM is exhausted;
+    /// backing the arena by a file lets the OS page cold subtrees to disk so big
+    /// trees can still finish solving on modest machines, at the cost of slower
+    /// access to pages that aren't resident.
+    ///
+    /// Creates the arena sized to the tree's exact real storage need (see
+    /// [`Self::total_storage_len`]), then walks the tree swapping every node's
+    /// `regrets`/`strategy_sum` (see [`NodeStorage`]) for a non-overlapping window
+    /// into the arena, copying across whatever was already stored — so this can be
+    /// called either before or after some solving has happened. A subtree shared by
+    /// [`GameConfig::merge_isomorphic_chances`] is only backed once, the same way
+    /// [`count_nodes`] only counts it once.
+    pub fn allocate_memory_mmap(&mut self, path: &Path) -> std::io::Result<()> {
+        let len = self.total_storage_len();
+        let mut arena = MmapArena::create(path, len)?;
+        Self::back_with_arena(&self.root, &mut arena, &mut 0, &mut HashSet::new());
+        self.mmap_arena = Some(arena);
+        self.storage_mode = StorageMode::MemoryMapped;
+        self.memory_allocated = true;
+        Ok(())
+    }
+
+    /// Returns which storage backend is currently allocated.
+    pub fn storage_mode(&self) -> StorageMode {
+        self.storage_mode
+    }
+
+    /// Sums the real per-node `regrets`/`strategy_sum` storage across the whole
+    /// tree, in `f32` elements, deduplicating shared subtrees by `Rc` pointer
+    /// identity exactly as [`count_nodes`] does. This is the exact size
+    /// [`Self::allocate_memory_mmap`] needs the arena to hold — unlike
+    /// [`Self::memory_usage`]'s one-size-fits-all `hands * 4` estimate, which is a
+    /// reporting figure, not a real allocation size.
+    fn total_storage_len(&self) -> usize {
+        fn walk(node: &Node, visited: &mut HashSet<*const Node>) -> usize {
+            let mut total = node.regrets.lock().len() + node.strategy_sum.lock().len();
+            for child in &node.children {
+                if visited.insert(Rc::as_ptr(child)) {
+                    total += walk(child, visited);
+                }
+            }
+            total
+        }
+        walk(&self.root, &mut HashSet::new())
+    }
+
+    /// Swaps `node`'s (and every not-yet-visited descendant's) `regrets`/
+    /// `strategy_sum` for non-overlapping windows into `arena`, advancing `offset` by
+    /// each window's length. Visits nodes through a shared `&Node` — `MutexLike`'s
+    /// interior mutability (see its module doc comment) lets this replace storage
+    /// without needing `&mut Node`, so a subtree shared via `Rc` (by
+    /// [`GameConfig::merge_isomorphic_chances`]) is backed exactly once rather than
+    /// once per parent that points at it. Traversal order must match
+    /// [`Self::total_storage_len`]'s so the windows handed out never run past the end
+    /// of `arena`.
+    fn back_with_arena(node: &Node, arena: &mut MmapArena, offset: &mut usize, visited: &mut HashSet<*const Node>) {
+        Self::back_storage_with_arena(&node.regrets, arena, offset);
+        Self::back_storage_with_arena(&node.strategy_sum, arena, offset);
+        for child in &node.children {
+            if visited.insert(Rc::as_ptr(child)) {
+                Self::back_with_arena(child, arena, offset, visited);
+            }
+        }
+    }
+
+    fn back_storage_with_arena(storage: &MutexLike<NodeStorage>, arena: &mut MmapArena, offset: &mut usize) {
+        let mut guard = storage.lock();
+        let len = guard.len();
+        if len == 0 {
+            return;
+        }
+        let ptr = arena.sub_ptr(*offset);
+        // Safety: `[*offset, *offset + len)` is reserved exclusively for this node by
+        // the matching traversal in `total_storage_len`/`back_with_arena`; no other
+        // node's window overlaps it.
+        unsafe { std::slice::from_raw_parts_mut(ptr.as_ptr(), len) }.copy_from_slice(&guard);
+        *guard = NodeStorage::Mmap { ptr, len };
+        *offset += len;
+    }
+
+    /// Copies every `Mmap`-backed node's storage back into its own `Vec`, the
+    /// reverse of [`Self::back_with_arena`]. Called by [`Self::allocate_memory`]
+    /// before dropping the arena so no node is left holding a dangling window.
+    fn restore_owned_storage(node: &Node, visited: &mut HashSet<*const Node>) {
+        for storage in [&node.regrets, &node.strategy_sum] {
+            let mut guard = storage.lock();
+            if matches!(*guard, NodeStorage::Mmap { .. }) {
+                *guard = NodeStorage::Owned(guard.to_vec());
+            }
+        }
+        for child in &node.children {
+            if visited.insert(Rc::as_ptr(child)) {
+                Self::restore_owned_storage(child, visited);
+            }
+        }
+    }
+}
+
+/// Counts the nodes reachable from `node`, deduplicating by `Rc` pointer identity so
+/// that a subtree shared by [`GameConfig::merge_isomorphic_chances`] is only counted
+/// once rather than once per isomorphic card that points at it.
+fn count_nodes(node: &Node) -> usize {
+    let mut visited = HashSet::new();
+    count_nodes_rec(node, &mut visited)
+}
+
+fn count_nodes_rec(node: &Node, visited: &mut HashSet<*const Node>) -> usize {
+    let mut total = 1;
+    for child in &node.children {
+        if visited.insert(Rc::as_ptr(child)) {
+            total += count_nodes_rec(child, visited);
+        }
+    }
+    total
+}
+
+/// Builds the action tree below the current board state.
+///
+/// Once the board is fully dealt (flop, turn and river all specified up front in
+/// `GameConfig`), this builds a real single-street betting round for the remaining
+/// action: OOP acts first with `Check` or a configured bet size; IP then has
+/// `Fold`/`Call`, plus a configured raise size if no raise has happened yet this
+/// street and a stack remains. At most one bet and one raise are modeled per street
+/// (no re-raise chains) — seeing further raises would require carrying bet-size
+/// candidates and the "is this the Nth raise" state across more than two decision
+/// levels, which this tree does not yet do.
+///
+/// For a config with any street left undealt, this still only produces chance nodes
+/// down to a payoff-less placeholder terminal at the river: making the decision tree
+/// above path-dependent on which card(s) get dealt (so the right range of *remaining*
+/// combos is tracked at each node) is a larger change than this pass makes, so that
+/// case is left exactly as it was.
+fn build_tree(config: &GameConfig, board: &[u8]) -> Node {
+    if board.len() >= 5 {
+        let originally_fully_dealt = config.flop.iter().all(|&c| c != NOT_DEALT)
+            && config.turn != NOT_DEALT
+            && config.river != NOT_DEALT;
+        if originally_fully_dealt {
+            return build_betting_round(config, board);
+        }
+        return Node::placeholder_terminal();
+    }
+
+    let cards = dealable_cards(config.variant, board);
+
+    let children = if config.merge_isomorphic_chances && board.len() >= 3 {
+        // Only turn/river chance nodes (board.len() >= 3, i.e. flop already dealt)
+        // are collapsed: both ranges are suit-blind in this crate's representation,
+        // so any suit that hasn't appeared on the board yet is interchangeable with
+        // any other unseen suit, and dealing one of a canonical class of ranks/suits
+        // produces a subtree isomorphic to dealing any other member of that class.
+        let mut built: HashMap<u8, Rc<Node>> = HashMap::new();
+        cards
+            .iter()
+            .map(|&card| {
+                let canonical = canonical_chance_card(card, board);
+                built
+                    .entry(canonical)
+                    .or_insert_with(|| {
+                        let mut next_board = board.to_vec();
+                        next_board.push(canonical);
+                        Rc::new(build_tree(config, &next_board))
+                    })
+                    .clone()
+            })
+            .collect()
+    } else {
+        cards
+            .iter()
+            .map(|&card| {
+                let mut next_board = board.to_vec();
+                next_board.push(card);
+                Rc::new(build_tree(config, &next_board))
+            })
+            .collect()
+    };
+
+    Node::chance(cards.into_iter().map(Action::Chance).collect(), children)
+}
+
+/// Builds the single-street betting round for a fully-dealt board: OOP to act first,
+/// no prior commitment this street.
+fn build_betting_round(config: &GameConfig, board: &[u8]) -> Node {
+    let board_mask = board.iter().fold(0u64, |m, &c| m | (1 << c));
+    let combos = [
+        crate::range::hand_combos(&config.range[0], config.variant, board_mask),
+        crate::range::hand_combos(&config.range[1], config.variant, board_mask),
+    ];
+    let num_hands = [combos[0].len(), combos[1].len()];
+    let showdown = Rc::new(ShowdownTable::build(&combos[0], &combos[1], board, config.variant));
+    let stacks = [config.effective_stack, config.effective_stack];
+    build_open(config, num_hands, &showdown, 0, false, [0, 0], stacks)
+}
+
+/// Builds the decision node for a player who is not currently facing a bet this
+/// street: `Check`, or a configured bet size. `second_to_act` is `true` once OOP has
+/// already checked, so an unopened IP check ends the street at showdown instead of
+/// passing the action back.
+fn build_open(
+    config: &GameConfig,
+    num_hands: [usize; 2],
+    showdown: &Rc<ShowdownTable>,
+    to_act: usize,
+    second_to_act: bool,
+    committed: [i32; 2],
+    stacks: [i32; 2],
+) -> Node {
+    let opponent = 1 - to_act;
+    let pot = config.starting_pot + committed[0] + committed[1];
+
+    let mut actions = vec![Action::Check];
+    let mut children = vec![Rc::new(if second_to_act {
+        Node::showdown_terminal(pot, committed, showdown.clone())
+    } else {
+        build_open(config, num_hands, showdown, opponent, true, committed, stacks)
+    })];
+
+    if stacks[to_act] > 0 {
+        let candidates = &street_bet_sizes(config, to_act).bet;
+        for size in compute_bet_sizes(pot, stacks[to_act], candidates, config) {
+            let mut new_committed = committed;
+            new_committed[to_act] += size;
+            let mut new_stacks = stacks;
+            new_stacks[to_act] -= size;
+            actions.push(if size == stacks[to_act] { Action::AllIn(size) } else { Action::Bet(size) });
+            children.push(Rc::new(build_facing(
+                config,
+                num_hands,
+                showdown,
+                opponent,
+                new_committed,
+                new_stacks,
+                false,
+            )));
+        }
+    }
+
+    Node::decision(player_for(to_act), actions, children, num_hands[to_act], showdown.clone())
+}
+
+/// Builds the decision node for a player facing a bet: `Fold`, `Call`, plus a
+/// configured raise size if `raised` is `false` (no raise has happened yet this
+/// street) and a stack remains after calling.
+fn build_facing(
+    config: &GameConfig,
+    num_hands: [usize; 2],
+    showdown: &Rc<ShowdownTable>,
+    to_act: usize,
+    committed: [i32; 2],
+    stacks: [i32; 2],
+    raised: bool,
+) -> Node {
+    let opponent = 1 - to_act;
+    let gap = committed[opponent] - committed[to_act];
+    let pot_before_call = config.starting_pot + committed[0] + committed[1];
+
+    let mut called_committed = committed;
+    called_committed[to_act] += gap;
+    let mut called_stacks = stacks;
+    called_stacks[to_act] -= gap;
+    let pot_after_call = pot_before_call + gap;
+
+    let mut actions = vec![Action::Fold, Action::Call];
+    let mut children = vec![
+        Rc::new(Node::fold_terminal(to_act, pot_before_call, committed, showdown.clone())),
+        Rc::new(Node::showdown_terminal(pot_after_call, called_committed, showdown.clone())),
+    ];
+
+    if !raised && called_stacks[to_act] > 0 {
+        let candidates = &street_bet_sizes(config, to_act).raise;
+        for size in compute_bet_sizes(pot_after_call, called_stacks[to_act], candidates, config) {
+            let mut new_committed = called_committed;
+            new_committed[to_act] += size;
+            let mut new_stacks = called_stacks;
+            new_stacks[to_act] -= size;
+            let total_this_action = gap + size;
+            actions.push(if size == called_stacks[to_act] {
+                Action::AllIn(total_this_action)
+            } else {
+                Action::Raise(total_this_action)
+            });
+            children.push(Rc::new(build_facing(
+                config,
+                num_hands,
+                showdown,
+                opponent,
+                new_committed,
+                new_stacks,
+                true,
+            )));
+        }
+    }
+
+    Node::decision(player_for(to_act), actions, children, num_hands[to_act], showdown.clone())
+}
+
+fn player_for(to_act: usize) -> Player {
+    if to_act == 0 {
+        Player::Oop
+    } else {
+        Player::Ip
+    }
+}
+
+/// Returns `player`'s configured bet/raise candidates for the street currently being
+/// built. Since [`build_tree`] only builds a real betting round for a board that was
+/// fully dealt up front, that's always the river street's candidates.
+fn street_bet_sizes(config: &GameConfig, player: usize) -> &BetSizeCandidates {
+    &config.river_bet_sizes[player]
+}
+
+/// Converts `candidates` (pot-relative percentages and/or an all-in shove) into
+/// absolute chip sizes for a bet/raise of `pot` into a stack of `stack`, honoring
+/// [`GameConfig::force_all_in_threshold`], [`GameConfig::add_all_in_threshold`] and
+/// [`GameConfig::adjust_last_two_bet_sizes`]. Returns sizes in ascending order, deduped.
+fn compute_bet_sizes(pot: i32, stack: i32, candidates: &[BetSize], config: &GameConfig) -> Vec<i32> {
+    if stack <= 0 || pot <= 0 || candidates.is_empty() {
+        return Vec::new();
+    }
+
+    let mut sizes: Vec<i32> = candidates
+        .iter()
+        .map(|size| match size {
+            BetSize::AllIn => stack,
+            BetSize::PotRelative(pct) => (((pot as f64) * pct / 100.0).round() as i32).clamp(1, stack),
+        })
+        .collect();
+
+    // Force a size that's already within `force_all_in_threshold` of the full stack
+    // up to exactly the stack, so near-all-in sizes don't leave an awkward few chips
+    // behind.
+    if config.force_all_in_threshold > 0.0 {
+        for size in &mut sizes {
+            if (stack - *size) as f64 <= stack as f64 * config.force_all_in_threshold {
+                *size = stack;
+            }
+        }
+    }
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    // Offer an explicit all-in alongside the largest configured size if that size is
+    // already close (within `add_all_in_threshold`) to shoving, so the tree still
+    // reaches a true all-in even when no candidate maps exactly onto the stack.
+    if config.add_all_in_threshold > 0.0 {
+        if let Some(&largest) = sizes.last() {
+            if largest != stack && (stack - largest) as f64 <= stack as f64 * config.add_all_in_threshold {
+                sizes.push(stack);
+            }
+        }
+    }
+    sizes.sort_unstable();
+    sizes.dedup();
+
+    // Drop the second-largest size when it's too close to the largest (within 10% of
+    // pot) to be worth offering as a separate branch.
+    if config.adjust_last_two_bet_sizes && sizes.len() >= 2 {
+        let n = sizes.len();
+        if ((sizes[n - 1] - sizes[n - 2]) as f64) < 0.1 * pot as f64 {
+            sizes.remove(n - 2);
+        }
+    }
+
+    sizes
+}
+
+/// Returns every card that can legally be dealt at the current chance node: part of
+/// `variant`'s deck and not already on `board`.
+fn dealable_cards(variant: Variant, board: &[u8]) -> Vec<u8> {
+    (0..52u8)
+        .filter(|&c| variant.contains_card(c) && !board.contains(&c))
+        .collect()
+}
+
+/// Maps `card` to the canonical representative of its suit-isomorphism class given
+/// the current `board`.
+///
+/// Both players' ranges in this crate are suit-blind (see [`Range`]'s 13x13 grid
+/// representation), so the permutation group that leaves them invariant is the full
+/// symmetric group over the suits that don't yet appear on `board`. Within that
+/// group, `card`'s orbit is every card of the same rank whose suit is also unseen on
+/// the board; its canonical representative is the lowest-indexed such suit.
+fn canonical_chance_card(card: u8, board: &[u8]) -> u8 {
+    let rank = card >> 2;
+    let suit = card & 3;
+
+    let used_suits: Vec<u8> = board.iter().map(|&c| c & 3).collect();
+    if used_suits.contains(&suit) {
+        // This card's suit already appears on the board, so it isn't part of any
+        // free-suit orbit; it's its own canonical representative.
+        return card;
+    }
+
+    let canonical_suit = (0..4)
+        .find(|s| !used_suits.contains(s))
+        .expect("at least one free suit exists whenever `suit` itself is free");
+    rank * 4 + canonical_suit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card_from_str;
+
+    /// A config whose board is already fully dealt, so `build_tree` builds a real
+    /// betting round instead of expanding the (still placeholder, chance-node-only)
+    /// tree for an undealt street.
+    fn fully_dealt_config() -> GameConfig {
+        GameConfig {
+            flop: [
+                card_from_str("Td").unwrap(),
+                card_from_str("9d").unwrap(),
+                card_from_str("6h").unwrap(),
+            ],
+            turn: card_from_str("Qh").unwrap(),
+            river: card_from_str("2c").unwrap(),
+            starting_pot: 100,
+            effective_stack: 400,
+            range: ["AA".parse().unwrap(), "KK".parse().unwrap()],
+            river_bet_sizes: [
+                BetSizeCandidates::try_from(("50%", "100%")).unwrap(),
+                BetSizeCandidates::try_from(("50%", "100%")).unwrap(),
+            ],
+            ..GameConfig::default()
+        }
+    }
+
+    #[test]
+    fn fully_dealt_board_has_a_real_decision_root() {
+        let game = PostFlopGame::with_config(&fully_dealt_config()).unwrap();
+        assert_eq!(game.root().player, Player::Oop);
+        assert_eq!(game.root().actions, vec![Action::Check, Action::Bet(50)]);
+    }
+
+    #[test]
+    fn checking_through_reaches_a_showdown_terminal() {
+        let game = PostFlopGame::with_config(&fully_dealt_config()).unwrap();
+        let check_ip = &game.root().children[0];
+        assert_eq!(check_ip.player, Player::Ip);
+        // IP still gets to bet after an OOP check; only IP's own `Check` ends the street.
+        assert_eq!(check_ip.actions, vec![Action::Check, Action::Bet(50)]);
+        let showdown = &check_ip.children[0];
+        assert_eq!(showdown.player, Player::Terminal);
+        assert!(showdown.terminal.unwrap().fold_winner.is_none());
+        assert!(showdown.showdown.is_some());
+    }
+
+    #[test]
+    fn facing_a_bet_offers_fold_call_and_one_raise_size() {
+        let game = PostFlopGame::with_config(&fully_dealt_config()).unwrap();
+        let facing_bet = &game.root().children[1];
+        assert_eq!(facing_bet.player, Player::Ip);
+        assert_eq!(facing_bet.actions[0], Action::Fold);
+        assert_eq!(facing_bet.actions[1], Action::Call);
+        assert_eq!(facing_bet.actions.len(), 3);
+
+        let fold_node = &facing_bet.children[0];
+        let payoff = fold_node.terminal.unwrap();
+        assert_eq!(payoff.fold_winner, Some(0));
+
+        // No re-raise chain: once IP has raised, OOP is only ever offered fold/call.
+        let raised = &facing_bet.children[2];
+        assert_eq!(raised.player, Player::Oop);
+        assert_eq!(raised.actions, vec![Action::Fold, Action::Call]);
+    }
+
+    #[test]
+    fn lock_node_strategy_works_on_the_real_decision_root() {
+        let mut game = PostFlopGame::with_config(&fully_dealt_config()).unwrap();
+        let num_hands = game.root().num_hands();
+        let num_actions = game.root().actions.len();
+        let frequencies = vec![1.0 / num_actions as f32; num_hands * num_actions];
+        assert!(game.lock_node_strategy(&[], frequencies).is_ok());
+        assert!(game.root().locked_strategy.is_some());
+    }
+
+    #[test]
+    fn lock_node_strategy_rejects_non_decision_node() {
+        // Checking through on both streets reaches a showdown terminal node, not a
+        // decision node, so locking there must fail.
+        let mut game = PostFlopGame::with_config(&fully_dealt_config()).unwrap();
+        assert!(game.lock_node_strategy(&[0, 0], vec![1.0]).is_err());
+    }
+
+    #[test]
+    fn unlock_node_strategy_on_unlocked_node_is_a_no_op() {
+        let mut game = PostFlopGame::with_config(&fully_dealt_config()).unwrap();
+        assert!(game.unlock_node_strategy(&[]).is_ok());
+    }
+
+    /// `merge_isomorphic_chances` shares one subtree across isomorphic turn/river
+    /// cards; `memory_usage` must actually reflect the resulting deduplicated node
+    /// count rather than counting a shared `Rc<Node>` subtree once per card that
+    /// points at it. (As the field's doc comment notes, every node on this path is
+    /// currently a placeholder terminal, so this is a real reduction in node count,
+    /// not yet in the regret/strategy storage the option is meant to cut.)
+    #[test]
+    fn merge_isomorphic_chances_reduces_reported_memory_usage() {
+        let base = GameConfig {
+            flop: [
+                card_from_str("Td").unwrap(),
+                card_from_str("9d").unwrap(),
+                card_from_str("6h").unwrap(),
+            ],
+            starting_pot: 100,
+            effective_stack: 400,
+            range: ["AA".parse().unwrap(), "KK".parse().unwrap()],
+            ..GameConfig::default()
+        };
+        let mut merged = base.clone();
+        merged.merge_isomorphic_chances = true;
+
+        let plain = PostFlopGame::with_config(&base).unwrap();
+        let isomorphic = PostFlopGame::with_config(&merged).unwrap();
+        assert!(isomorphic.memory_usage().0 < plain.memory_usage().0);
+    }
+
+    /// `allocate_memory_mmap` must actually back the solver's storage, not just map a
+    /// file no one reads or writes through: solving after switching to
+    /// `MemoryMapped` should accumulate the same nonzero regret as solving against
+    /// plain in-RAM storage would.
+    #[test]
+    fn solving_after_allocate_memory_mmap_accumulates_real_regret() {
+        let mut game = PostFlopGame::with_config(&fully_dealt_config()).unwrap();
+        let path = std::env::temp_dir().join(format!(
+            "postflop-solver-test-solve-{}.mmap",
+            std::process::id()
+        ));
+        game.allocate_memory_mmap(&path).unwrap();
+        assert_eq!(game.storage_mode(), StorageMode::MemoryMapped);
+        assert!(matches!(*game.root().regrets.lock(), NodeStorage::Mmap { .. }));
+
+        for i in 0..10 {
+            crate::solve_step(&game, i);
+        }
+        assert!(game.max_abs_regret() > 0.0);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    /// Switching back to in-RAM storage after a memory-mapped allocation must leave
+    /// every node's storage in its own `Vec` (not a dangling window into the
+    /// now-dropped arena) while preserving the regret accumulated so far.
+    #[test]
+    fn allocate_memory_after_mmap_restores_owned_storage_and_preserves_values() {
+        let mut game = PostFlopGame::with_config(&fully_dealt_config()).unwrap();
+        let path = std::env::temp_dir().join(format!(
+            "postflop-solver-test-restore-{}.mmap",
+            std::process::id()
+        ));
+        game.allocate_memory_mmap(&path).unwrap();
+        for i in 0..10 {
+            crate::solve_step(&game, i);
+        }
+        let regret_before = game.max_abs_regret();
+        assert!(regret_before > 0.0);
+
+        game.allocate_memory(false);
+        assert_eq!(game.storage_mode(), StorageMode::Uncompressed);
+        assert!(matches!(*game.root().regrets.lock(), NodeStorage::Owned(_)));
+        assert_eq!(game.max_abs_regret(), regret_before);
+
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,244 @@
+//! 7-card hand evaluator: the only one this crate has.
+//!
+//! Short-deck's ranking swaps (this module's [`HandRank::cmp_for_variant`]) only ever
+//! landed here. The request that introduced [`Variant::ShortDeck`] asked for short-deck
+//! rankings to also apply through a second, faster evaluation path backed by the
+//! external `holdem-hand-evaluator` crate, but that crate was never vendored into this
+//! tree, so a `holdem-hand-evaluator` feature flag shipped as dead weight: enabling it
+//! changed nothing, since there was no second path to apply short-deck rankings to.
+//! That flag has since been removed from `Cargo.toml` rather than left advertising a
+//! capability this crate doesn't have; vendoring the real dependency and wiring
+//! variant-aware ranking through it remains undone.
+
+use crate::Variant;
+
+/// Hand category, ordered so that a larger discriminant always beats a smaller one
+/// under [`Variant::Standard`] rules.
+///
+/// Under [`Variant::ShortDeck`] rules a flush outranks a full house, and three of a kind
+/// outranks a straight (there are fewer ways to make either a flush or a straight with
+/// only 36 cards in the deck), so [`HandRank`] comparisons must go through
+/// [`HandRank::cmp_for_variant`] rather than relying on derived `Ord`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandCategory {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+/// The evaluated strength of a 5-to-7 card hand: a category plus rank-value kickers
+/// used to break ties within the same category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandRank {
+    pub category: HandCategory,
+    /// Tie-break values, most significant first (e.g., `[quad_rank, kicker]`).
+    pub tiebreak: [u8; 5],
+}
+
+impl HandRank {
+    /// Compares two hand ranks under the rules of `variant`.
+    ///
+    /// Under [`Variant::ShortDeck`], flush and full house swap places in the ranking order,
+    /// and three of a kind and straight swap places as well (trips beats a straight).
+    pub fn cmp_for_variant(&self, other: &Self, variant: Variant) -> std::cmp::Ordering {
+        let key = |h: &HandRank| -> (u8, [u8; 5]) {
+            let rank = match (variant, h.category) {
+                (Variant::ShortDeck, HandCategory::Flush) => HandCategory::FullHouse as u8 + 1,
+                (Variant::ShortDeck, HandCategory::FullHouse) => HandCategory::Flush as u8,
+                (Variant::ShortDeck, HandCategory::ThreeOfAKind) => HandCategory::Straight as u8,
+                (Variant::ShortDeck, HandCategory::Straight) => HandCategory::ThreeOfAKind as u8,
+                _ => h.category as u8,
+            };
+            (rank, h.tiebreak)
+        };
+        key(self).cmp(&key(other))
+    }
+}
+
+/// Evaluates the best 5-card hand out of `cards` (5, 6, or 7 cards), honoring `variant`'s
+/// hand-ranking rules (short-deck swaps the flush/full-house order and allows the
+/// `A-6-7-8-9` low straight in place of the removed `A-2-3-4-5` wheel).
+pub fn evaluate_hand(cards: &[u8], variant: Variant) -> HandRank {
+    let mut best: Option<HandRank> = None;
+    for combo in combinations(cards, 5) {
+        let rank = evaluate_five(&combo, variant);
+        best = Some(match best {
+            Some(current) if current.cmp_for_variant(&rank, variant).is_ge() => current,
+            _ => rank,
+        });
+    }
+    best.expect("at least 5 cards are required to evaluate a hand")
+}
+
+fn combinations(cards: &[u8], k: usize) -> Vec<Vec<u8>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    let mut result = Vec::new();
+    for i in 0..cards.len() {
+        for mut tail in combinations(&cards[i + 1..], k - 1) {
+            tail.insert(0, cards[i]);
+            result.push(tail);
+        }
+    }
+    result
+}
+
+fn evaluate_five(cards: &[u8], variant: Variant) -> HandRank {
+    let mut ranks: Vec<u8> = cards.iter().map(|&c| c >> 2).collect();
+    let suits: Vec<u8> = cards.iter().map(|&c| c & 3).collect();
+    ranks.sort_unstable_by(|a, b| b.cmp(a));
+
+    let is_flush = suits.iter().all(|&s| s == suits[0]);
+    let straight_high = straight_high_rank(&ranks, variant);
+
+    let mut counts = [0u8; 13];
+    for &r in &ranks {
+        counts[r as usize] += 1;
+    }
+    let mut by_count: Vec<(u8, u8)> = (0..13)
+        .filter(|&r| counts[r] > 0)
+        .map(|r| (counts[r], r as u8))
+        .collect();
+    by_count.sort_unstable_by(|a, b| b.cmp(a));
+
+    let tiebreak = |values: &[u8]| -> [u8; 5] {
+        let mut out = [0u8; 5];
+        out[..values.len().min(5)].copy_from_slice(&values[..values.len().min(5)]);
+        out
+    };
+
+    if is_flush {
+        if let Some(high) = straight_high {
+            return HandRank {
+                category: HandCategory::StraightFlush,
+                tiebreak: tiebreak(&[high]),
+            };
+        }
+    }
+    if by_count[0].0 == 4 {
+        let kicker = by_count[1].1;
+        return HandRank {
+            category: HandCategory::FourOfAKind,
+            tiebreak: tiebreak(&[by_count[0].1, kicker]),
+        };
+    }
+    if by_count[0].0 == 3 && by_count[1].0 == 2 {
+        return HandRank {
+            category: HandCategory::FullHouse,
+            tiebreak: tiebreak(&[by_count[0].1, by_count[1].1]),
+        };
+    }
+    if is_flush {
+        return HandRank {
+            category: HandCategory::Flush,
+            tiebreak: tiebreak(&ranks),
+        };
+    }
+    if let Some(high) = straight_high {
+        return HandRank {
+            category: HandCategory::Straight,
+            tiebreak: tiebreak(&[high]),
+        };
+    }
+    if by_count[0].0 == 3 {
+        let kickers: Vec<u8> = by_count[1..].iter().map(|&(_, r)| r).collect();
+        return HandRank {
+            category: HandCategory::ThreeOfAKind,
+            tiebreak: tiebreak(&[&[by_count[0].1][..], &kickers[..]].concat()),
+        };
+    }
+    if by_count[0].0 == 2 && by_count[1].0 == 2 {
+        let kicker = by_count[2].1;
+        return HandRank {
+            category: HandCategory::TwoPair,
+            tiebreak: tiebreak(&[by_count[0].1, by_count[1].1, kicker]),
+        };
+    }
+    if by_count[0].0 == 2 {
+        let kickers: Vec<u8> = by_count[1..].iter().map(|&(_, r)| r).collect();
+        return HandRank {
+            category: HandCategory::OnePair,
+            tiebreak: tiebreak(&[&[by_count[0].1][..], &kickers[..]].concat()),
+        };
+    }
+    HandRank {
+        category: HandCategory::HighCard,
+        tiebreak: tiebreak(&ranks),
+    }
+}
+
+/// Returns the high card of a straight among `ranks` (descending, deduplicated by caller
+/// via the `counts` check happening at the call site), if any.
+///
+/// Under [`Variant::ShortDeck`], the deck has no 2-5, so the wheel straight becomes
+/// `A-6-7-8-9` instead of the standard `A-2-3-4-5`.
+fn straight_high_rank(ranks: &[u8], variant: Variant) -> Option<u8> {
+    let mut unique: Vec<u8> = ranks.to_vec();
+    unique.dedup();
+    if unique.len() < 5 {
+        return None;
+    }
+
+    for window in unique.windows(5) {
+        if window[0] - window[4] == 4 {
+            return Some(window[0]);
+        }
+    }
+
+    // Ace-low straight: standard wheel is A-2-3-4-5; short-deck's lowest straight
+    // (since 2-5 don't exist) is A-6-7-8-9.
+    let ace = 12u8;
+    if unique.contains(&ace) {
+        let low_run: &[u8] = match variant {
+            Variant::Standard => &[3, 2, 1, 0],
+            Variant::ShortDeck => &[7, 6, 5, 4],
+        };
+        if low_run.iter().all(|r| unique.contains(r)) {
+            return Some(low_run[0]);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::range::card_from_str;
+
+    fn cards(strs: &[&str]) -> Vec<u8> {
+        strs.iter().map(|s| card_from_str(s).unwrap()).collect()
+    }
+
+    #[test]
+    fn short_deck_flush_beats_full_house() {
+        let flush = evaluate_hand(&cards(&["9c", "Tc", "Jc", "Qc", "Kc"]), Variant::ShortDeck);
+        let full_house = evaluate_hand(&cards(&["9c", "9d", "9h", "Ks", "Kc"]), Variant::ShortDeck);
+        assert_eq!(flush.cmp_for_variant(&full_house, Variant::ShortDeck), std::cmp::Ordering::Greater);
+    }
+
+    #[test]
+    fn short_deck_trips_beats_straight() {
+        // Kickers ("8s", "7c") are in-deck ranks for `ShortDeck` (6-A); a short-deck
+        // fixture shouldn't use "2"-"5" ranks that could never appear in a real hand.
+        let trips = evaluate_hand(&cards(&["9c", "9d", "9h", "8s", "7c"]), Variant::ShortDeck);
+        let straight = evaluate_hand(&cards(&["9c", "Td", "Jh", "Qs", "Kc"]), Variant::ShortDeck);
+        assert_eq!(trips.category, HandCategory::ThreeOfAKind);
+        assert_eq!(straight.category, HandCategory::Straight);
+        assert_eq!(trips.cmp_for_variant(&straight, Variant::ShortDeck), std::cmp::Ordering::Greater);
+    }
+
+    #[test]
+    fn standard_deck_straight_beats_trips() {
+        let trips = evaluate_hand(&cards(&["9c", "9d", "9h", "4s", "7c"]), Variant::Standard);
+        let straight = evaluate_hand(&cards(&["9c", "Td", "Jh", "Qs", "Kc"]), Variant::Standard);
+        assert_eq!(straight.cmp_for_variant(&trips, Variant::Standard), std::cmp::Ordering::Greater);
+    }
+}
@@ -0,0 +1,23 @@
+//! Shared action/node types used by the game tree and the interpreter.
+
+/// An action available at a decision node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Bet(i32),
+    Raise(i32),
+    AllIn(i32),
+    /// A chance node; the payload is the dealt card.
+    Chance(u8),
+}
+
+/// Which player (or chance) is to act at a node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Player {
+    Oop,
+    Ip,
+    Chance,
+    Terminal,
+}
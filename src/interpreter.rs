@@ -0,0 +1,475 @@
+//! Interactive traversal and inspection of a solved [`PostFlopGame`] tree.
+
+use crate::{card_to_string, compute_average, Action, Node, Player, PostFlopGame};
+
+/// Default steepness constant `k` for [`Interpreter::win_probability`], fit so that a
+/// break-even EV (`0` pot fractions) maps to a `0.5` win probability, which holds for
+/// any `k` since `sigmoid(0) = 0.5`; this value just controls how quickly the
+/// estimate saturates away from break-even.
+pub const DEFAULT_WIN_PROBABILITY_STEEPNESS: f32 = 3.0;
+
+/// Walks a solved [`PostFlopGame`] tree node by node, exposing the current node's
+/// available actions, equities and EVs, and the ability to `play` an action/card to
+/// move to a child node.
+#[derive(Clone)]
+pub struct Interpreter<'a> {
+    game: &'a PostFlopGame,
+    /// Path of child indices from the root to the current node.
+    path: Vec<usize>,
+    /// How much to bias the displayed strategy toward exploiting a fixed opponent
+    /// range rather than playing the GTO average strategy; `0.0` means "pure GTO".
+    exploitative_factor: f32,
+    normalized_weights: [Vec<f32>; 2],
+    /// Steepness constant used by [`Self::win_probability`]. See
+    /// [`DEFAULT_WIN_PROBABILITY_STEEPNESS`].
+    win_probability_steepness: f32,
+}
+
+impl<'a> Interpreter<'a> {
+    /// Creates an interpreter positioned at the root of `game`.
+    pub fn new(game: &'a PostFlopGame, exploitative_factor: f32) -> Self {
+        Self {
+            game,
+            path: Vec::new(),
+            exploitative_factor,
+            normalized_weights: [Vec::new(), Vec::new()],
+            win_probability_steepness: DEFAULT_WIN_PROBABILITY_STEEPNESS,
+        }
+    }
+
+    /// Sets the steepness constant `k` used by [`Self::win_probability`].
+    pub fn set_win_probability_steepness(&mut self, k: f32) {
+        self.win_probability_steepness = k;
+    }
+
+    fn current_node(&self) -> &Node {
+        let mut node = self.game.root();
+        for &index in &self.path {
+            node = &node.children[index];
+            // `node.children` holds `Rc<Node>` so isomorphic chance subtrees can be
+            // shared (see `GameConfig::merge_isomorphic_chances`); `Node` methods are
+            // reached through the `Rc`'s `Deref` impl transparently.
+        }
+        node
+    }
+
+    /// Recomputes the cached normalized hand weights for both players at the current node.
+    pub fn cache_normalized_weights(&mut self) {
+        for player in 0..2 {
+            let weights = self.game.hand_weights(player);
+            let sum: f32 = weights.iter().sum();
+            self.normalized_weights[player] = if sum > 0.0 {
+                weights.iter().map(|w| w / sum).collect()
+            } else {
+                weights
+            };
+        }
+    }
+
+    /// Returns the cached normalized weights for `player` (see [`Self::cache_normalized_weights`]).
+    pub fn normalized_weights(&self, player: usize) -> &[f32] {
+        &self.normalized_weights[player]
+    }
+
+    /// Returns which player is to act at the current node (0 = OOP, 1 = IP).
+    pub fn current_player(&self) -> usize {
+        match self.current_node().player {
+            Player::Ip => 1,
+            _ => 0,
+        }
+    }
+
+    /// Returns `true` if the current node is a chance node (a card is about to be dealt).
+    pub fn is_chance_node(&self) -> bool {
+        self.current_node().player == Player::Chance
+    }
+
+    /// Returns a bitmask of the cards that may be dealt at the current chance node.
+    pub fn possible_cards(&self) -> u64 {
+        let mut mask = 0u64;
+        for action in &self.current_node().actions {
+            if let Action::Chance(card) = action {
+                mask |= 1 << card;
+            }
+        }
+        mask
+    }
+
+    /// Returns the actions available at the current node.
+    pub fn available_actions(&self) -> Vec<Action> {
+        self.current_node().actions.clone()
+    }
+
+    /// Moves to the child node reached by playing the action/card at `index` in
+    /// [`Self::available_actions`].
+    pub fn play(&mut self, index: usize) {
+        assert!(index < self.current_node().actions.len(), "action index out of range");
+        self.path.push(index);
+    }
+
+    /// Returns the per-hand expected value at the current node for the player to act:
+    /// a blend of the GTO value (under both players' current
+    /// [`Node::average_strategy`], cached by [`crate::finalize`]) and the fully
+    /// exploitative value (the player's best response to the opponent's average
+    /// strategy from this node on), mixed by `exploitative_factor` (`0.0` = pure GTO,
+    /// `1.0` = pure best response).
+    ///
+    /// `0.0` for every hand until [`crate::finalize`] has run at least once (the GTO
+    /// term reads [`Node::cfv`], which starts empty).
+    pub fn expected_values(&self) -> Vec<f32> {
+        let player = self.current_player();
+        let node = self.current_node();
+        let gto_ev = node.cfv.lock()[player].clone();
+        if gto_ev.is_empty() {
+            return vec![0.0; self.normalized_weights[player].len()];
+        }
+        if self.exploitative_factor == 0.0 {
+            return gto_ev;
+        }
+        let opponent = 1 - player;
+        // `node.cfv` was populated by `finalize`, which (like `compute_exploitability`)
+        // reaches `best_response` with `PostFlopGame::hand_weights`'s raw combo
+        // weights, not `self.normalized_weights`'s sum-to-one weights; matching that
+        // scale here is what makes `gto` and `br` comparable in the blend below.
+        let br_ev = crate::solver::best_response(node, player, &self.game.hand_weights(opponent), gto_ev.len());
+        gto_ev
+            .iter()
+            .zip(br_ev.iter())
+            .map(|(&gto, &br)| gto + self.exploitative_factor * (br - gto))
+            .collect()
+    }
+
+    /// Returns the per-hand raw showdown equity at the current node for the player to
+    /// act against the opponent's whole range (ignoring betting strategy entirely):
+    /// the reach-weighted fraction of opponent combos each hand beats, counting a tie
+    /// as half a win. `0.0` for every hand at a node with no showdown table (a chance
+    /// node, or the placeholder terminal of an undealt street — see
+    /// [`Node::showdown_table`]).
+    pub fn equity(&self) -> Vec<f32> {
+        let player = self.current_player();
+        let node = self.current_node();
+        let Some(showdown) = node.showdown_table() else {
+            return vec![0.0; self.normalized_weights[player].len()];
+        };
+        let opponent = 1 - player;
+        let reach_opp = &self.normalized_weights[opponent];
+        (0..self.normalized_weights[player].len())
+            .map(|hand| showdown.win_fraction(hand, player == 0, reach_opp))
+            .collect()
+    }
+
+    /// Returns the per-hand, per-action strategy frequencies at the current decision
+    /// node for the player to act: one entry per hand per available action, matching
+    /// [`Self::available_actions`]'s order (the same layout
+    /// [`crate::PostFlopGame::lock_node_strategy`] expects).
+    ///
+    /// If the node is locked, this is exactly the locked frequencies. At a decision
+    /// node, this is [`Node::average_strategy`]. At a chance node (or the placeholder
+    /// terminal of an undealt street — there is no strategy to report there), this
+    /// falls back to a uniform distribution over actions.
+    pub fn action_frequencies(&self) -> Vec<f32> {
+        let node = self.current_node();
+        if let Some(locked) = &node.locked_strategy {
+            return locked.clone();
+        }
+        let num_actions = node.actions.len();
+        if num_actions == 0 {
+            return Vec::new();
+        }
+        if node.player == Player::Oop || node.player == Player::Ip {
+            return node.average_strategy();
+        }
+        let player = self.current_player();
+        let num_hands = self.normalized_weights[player].len();
+        vec![1.0 / num_actions as f32; num_hands * num_actions]
+    }
+
+    /// Recursively serializes the solved tree rooted at the current node to JSON.
+    ///
+    /// For every reachable decision node, emits the available actions, per-hand action
+    /// frequencies (see [`Self::action_frequencies`]), EVs and equities, so that
+    /// external tools and GUIs can consume the solved strategy without linking against
+    /// this crate. Chance nodes emit actions and children only; terminal nodes emit
+    /// just their type.
+    pub fn export_json(&self) -> String {
+        let mut interp = self.clone();
+        interp.cache_normalized_weights();
+        export_node_json(&interp)
+    }
+
+    /// Estimates the win probability of each hand at the current node from its EV,
+    /// via a sigmoid of the form `p = 1 / (1 + exp(-k * ev_in_pot_fractions))`, where
+    /// `k` is [`Self::set_win_probability_steepness`] (default
+    /// [`DEFAULT_WIN_PROBABILITY_STEEPNESS`]).
+    ///
+    /// This is a rough heuristic, not a statistically derived mapping: it gives users
+    /// a single interpretable number for how a node "feels" without claiming to be an
+    /// exact win rate.
+    ///
+    /// Note: until [`crate::finalize`] has run, [`Self::expected_values`] reads back
+    /// `0.0` for every hand (see its doc comment), and `sigmoid(0.0)` is exactly `0.5`
+    /// regardless of [`Self::win_probability_steepness`], so every value returned here
+    /// is `0.5` until then.
+    pub fn win_probability(&self) -> Vec<f32> {
+        let pot = self.game.config().starting_pot.max(1) as f32;
+        self.expected_values()
+            .iter()
+            .map(|&ev| sigmoid(self.win_probability_steepness * (ev / pot)))
+            .collect()
+    }
+
+    /// Range-weighted average win probability across the whole range of the player to
+    /// act at the current node (see [`Self::win_probability`]).
+    pub fn average_win_probability(&self) -> f32 {
+        let player = self.current_player();
+        compute_average(&self.win_probability(), self.normalized_weights(player))
+    }
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn export_node_json(interp: &Interpreter) -> String {
+    let node = interp.current_node();
+
+    if node.player == Player::Terminal {
+        return "{\"type\":\"terminal\"}".to_string();
+    }
+
+    let actions_json: Vec<String> = node
+        .actions
+        .iter()
+        .map(|action| format!("\"{}\"", action_label(action)))
+        .collect();
+
+    if node.player == Player::Chance {
+        let children_json: Vec<String> = (0..node.children.len())
+            .map(|i| {
+                let mut child = interp.clone();
+                child.play(i);
+                child.cache_normalized_weights();
+                export_node_json(&child)
+            })
+            .collect();
+        return format!(
+            "{{\"type\":\"chance\",\"actions\":[{}],\"children\":[{}]}}",
+            actions_json.join(","),
+            children_json.join(",")
+        );
+    }
+
+    let ev_json: Vec<String> = interp.expected_values().iter().map(|v| v.to_string()).collect();
+    let equity_json: Vec<String> = interp.equity().iter().map(|v| v.to_string()).collect();
+    let frequencies_json: Vec<String> = interp.action_frequencies().iter().map(|v| v.to_string()).collect();
+
+    let children_json: Vec<String> = (0..node.children.len())
+        .map(|i| {
+            let mut child = interp.clone();
+            child.play(i);
+            child.cache_normalized_weights();
+            export_node_json(&child)
+        })
+        .collect();
+
+    format!(
+        "{{\"type\":\"decision\",\"player\":{},\"actions\":[{}],\"frequencies\":[{}],\"ev\":[{}],\"equity\":[{}],\"children\":[{}]}}",
+        interp.current_player(),
+        actions_json.join(","),
+        frequencies_json.join(","),
+        ev_json.join(","),
+        equity_json.join(","),
+        children_json.join(",")
+    )
+}
+
+fn action_label(action: &Action) -> String {
+    match action {
+        Action::Fold => "Fold".to_string(),
+        Action::Check => "Check".to_string(),
+        Action::Call => "Call".to_string(),
+        Action::Bet(amount) => format!("Bet({amount})"),
+        Action::Raise(amount) => format!("Raise({amount})"),
+        Action::AllIn(amount) => format!("AllIn({amount})"),
+        Action::Chance(card) => card_to_string(*card).unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{finalize, flop_from_str, solve_step, BetSizeCandidates, GameConfig};
+
+    fn river_undealt_game() -> PostFlopGame {
+        let config = GameConfig {
+            flop: flop_from_str("Td9d6h").unwrap(),
+            turn: crate::card_from_str("Qh").unwrap(),
+            range: ["AA".parse().unwrap(), "KK".parse().unwrap()],
+            ..GameConfig::default()
+        };
+        PostFlopGame::with_config(&config).unwrap()
+    }
+
+    #[test]
+    fn action_frequencies_are_uniform_without_a_lock() {
+        let game = river_undealt_game();
+        let mut interp = Interpreter::new(&game, 0.0);
+        interp.cache_normalized_weights();
+
+        let num_actions = interp.available_actions().len();
+        let num_hands = interp.normalized_weights(interp.current_player()).len();
+        assert!(num_actions > 0);
+        assert!(num_hands > 0);
+
+        let frequencies = interp.action_frequencies();
+        assert_eq!(frequencies.len(), num_hands * num_actions);
+        for chunk in frequencies.chunks(num_actions) {
+            let sum: f32 = chunk.iter().sum();
+            assert!((sum - 1.0).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn win_probability_is_constant_on_an_undealt_street() {
+        // `river_undealt_game` leaves a street undealt, so (per `build_tree`'s doc
+        // comment) its tree is chance/placeholder-terminal nodes only; there's no
+        // real decision node for `finalize` to cache an EV for, so `expected_values`
+        // reads back `0.0` and every win probability here is exactly 0.5.
+        let game = river_undealt_game();
+        let mut interp = Interpreter::new(&game, 0.0);
+        interp.cache_normalized_weights();
+
+        for &p in interp.win_probability().iter() {
+            assert_eq!(p, 0.5);
+        }
+        assert_eq!(interp.average_win_probability(), 0.5);
+    }
+
+    #[test]
+    fn export_json_chance_node_shape() {
+        // `river_undealt_game` leaves a street undealt, so its tree only ever reaches
+        // chance/terminal nodes (see `crate::game::build_tree`'s doc comment); a
+        // `"frequencies"`/`"ev"`/`"equity"` field never appears in JSON exported from
+        // it. The decision-node branch that emits them is covered by the fully-dealt
+        // fixture tests below.
+        let game = river_undealt_game();
+        let mut interp = Interpreter::new(&game, 0.0);
+        interp.cache_normalized_weights();
+        let json = interp.export_json();
+        assert!(json.starts_with("{\"type\":\"chance\""));
+        assert!(json.contains("\"children\":["));
+    }
+
+    fn fully_dealt_game() -> PostFlopGame {
+        let config = GameConfig {
+            flop: flop_from_str("Td9d6h").unwrap(),
+            turn: crate::card_from_str("Qh").unwrap(),
+            river: crate::card_from_str("2c").unwrap(),
+            starting_pot: 100,
+            effective_stack: 400,
+            range: ["AA".parse().unwrap(), "KK".parse().unwrap()],
+            river_bet_sizes: [
+                BetSizeCandidates::try_from(("50%", "100%")).unwrap(),
+                BetSizeCandidates::try_from(("50%", "100%")).unwrap(),
+            ],
+            ..GameConfig::default()
+        };
+        PostFlopGame::with_config(&config).unwrap()
+    }
+
+    /// Like [`fully_dealt_game`], but OOP's range always loses at showdown and IP's
+    /// always wins (see `crate::solver`'s `bluff_fixture_config`, which this mirrors):
+    /// gives betting genuine downside for OOP, so a converged solve's strategy is
+    /// distinguishable from a uniform one.
+    fn bluff_fixture_game() -> PostFlopGame {
+        let mut config = fully_dealt_game().config().clone();
+        config.range = ["72o".parse().unwrap(), "AA".parse().unwrap()];
+        PostFlopGame::with_config(&config).unwrap()
+    }
+
+    #[test]
+    fn equity_reflects_raw_showdown_strength_not_strategy() {
+        // Every one of OOP's "AA" combos beats every one of IP's "KK" combos on this
+        // board, regardless of how either side plays.
+        let game = fully_dealt_game();
+        let mut interp = Interpreter::new(&game, 0.0);
+        interp.cache_normalized_weights();
+        assert_eq!(interp.equity(), vec![1.0; 6]);
+
+        interp.play(0); // OOP checks
+        interp.cache_normalized_weights();
+        assert_eq!(interp.equity(), vec![0.0; 6]); // every one of IP's hands always loses
+    }
+
+    #[test]
+    fn action_frequencies_reflect_average_strategy_after_solving() {
+        let game = bluff_fixture_game();
+        for i in 0..300 {
+            solve_step(&game, i);
+        }
+        let mut interp = Interpreter::new(&game, 0.0);
+        interp.cache_normalized_weights();
+        let num_actions = interp.available_actions().len();
+        let uniform = 1.0 / num_actions as f32;
+
+        let frequencies = interp.action_frequencies();
+        let check_freqs: Vec<f32> = frequencies.chunks(num_actions).map(|chunk| chunk[0]).collect();
+        let average_check_freq = check_freqs.iter().sum::<f32>() / check_freqs.len() as f32;
+        // OOP's only holding always loses at showdown, so after solving it should
+        // check far more often than the old uniform-over-actions placeholder.
+        assert!(
+            average_check_freq > uniform + 0.1,
+            "average check frequency {average_check_freq} should clearly exceed uniform {uniform}"
+        );
+    }
+
+    #[test]
+    fn expected_values_are_zero_until_finalize_then_real() {
+        let mut game = bluff_fixture_game();
+        let interp = Interpreter::new(&game, 0.0);
+        assert!(interp.expected_values().iter().all(|&ev| ev == 0.0));
+
+        for i in 0..300 {
+            solve_step(&game, i);
+        }
+        finalize(&mut game);
+
+        let mut interp = Interpreter::new(&game, 0.0);
+        interp.cache_normalized_weights();
+        let evs = interp.expected_values();
+        assert!(evs.iter().any(|&ev| ev != 0.0), "expected a real nonzero EV after finalize, got {evs:?}");
+        assert_ne!(interp.average_win_probability(), 0.5);
+    }
+
+    #[test]
+    fn expected_values_blend_toward_best_response_with_exploitative_factor() {
+        let mut game = bluff_fixture_game();
+        for i in 0..300 {
+            solve_step(&game, i);
+        }
+        finalize(&mut game);
+
+        let mut gto = Interpreter::new(&game, 0.0);
+        gto.cache_normalized_weights();
+        let gto_ev = gto.expected_values();
+
+        let mut exploit = Interpreter::new(&game, 1.0);
+        exploit.cache_normalized_weights();
+        let exploit_ev = exploit.expected_values();
+
+        assert!(
+            exploit_ev.iter().zip(gto_ev.iter()).any(|(&e, &g)| e > g + 1e-6),
+            "a pure best response should beat the GTO value somewhere on an unconverged solve: \
+             exploit={exploit_ev:?} gto={gto_ev:?}"
+        );
+
+        // At `exploitative_factor == 1.0`, `expected_values` should be exactly the
+        // best response computed directly against the opponent's raw (unnormalized)
+        // range weights — the same scale `compute_exploitability` itself uses — not
+        // the sum-to-one `normalized_weights`, which would silently rescale it.
+        let direct_br = crate::solver::best_response(game.root(), 0, &game.hand_weights(1), gto_ev.len());
+        for (&e, &d) in exploit_ev.iter().zip(direct_br.iter()) {
+            assert!((e - d).abs() < 1e-6, "exploit_ev {exploit_ev:?} should equal direct best response {direct_br:?}");
+        }
+    }
+}
@@ -1,6 +1,10 @@
 //! An open-source postflop solver library.
 //!
 //! # Examples
+//! This builds a real decision tree, so the board must be fully dealt up front (see
+//! [`GameConfig::river`] and [`crate::game::build_tree`]'s doc comment): solving a
+//! config with any street left [`NOT_DEALT`] only ever walks chance/placeholder-terminal
+//! nodes, with no real decisions to report.
 //! ```
 //! use postflop_solver::*;
 //!
@@ -9,18 +13,20 @@
 //! let ip_range = "QQ-22,AQs-A2s,ATo+,K5s+,KJo+,Q8s+,J8s+,T7s+,96s+,86s+,75s+,64s+,53s+";
 //! let bet_sizes = BetSizeCandidates::try_from(("50%", "50%")).unwrap();
 //! let config = GameConfig {
+//!     variant: Variant::Standard,
 //!     flop: flop_from_str("Td9d6h").unwrap(),
 //!     turn: card_from_str("Qh").unwrap(),
-//!     river: NOT_DEALT,
+//!     river: card_from_str("2c").unwrap(),
 //!     starting_pot: 200,
 //!     effective_stack: 900,
 //!     range: [oop_range.parse().unwrap(), ip_range.parse().unwrap()],
 //!     flop_bet_sizes: [bet_sizes.clone(), bet_sizes.clone()],
 //!     turn_bet_sizes: [bet_sizes.clone(), bet_sizes.clone()],
 //!     river_bet_sizes: [bet_sizes.clone(), bet_sizes.clone()],
-//!     add_all_in_threshold: 1.2,
-//!     force_all_in_threshold: 0.1,
-//!     adjust_last_two_bet_sizes: true,
+//!     add_all_in_threshold: 0.0,
+//!     force_all_in_threshold: 0.0,
+//!     adjust_last_two_bet_sizes: false,
+//!     merge_isomorphic_chances: false,
 //! };
 //!
 //! // build game tree
@@ -101,15 +107,9 @@
 //! // play `Call`
 //! interpreter.play(1);
 //!
-//! // confirm that the current node is a chance node
-//! assert!(interpreter.is_chance_node());
-//!
-//! // confirm that "7s" may be dealt
-//! let card = card_from_str("7s").unwrap();
-//! assert!(interpreter.possible_cards() & (1 << card) != 0);
-//!
-//! // deal "7s"
-//! interpreter.play(card as usize);
+//! // the board was fully dealt up front, so calling ends the hand at a showdown
+//! // terminal: there's nothing left to act on
+//! assert!(interpreter.available_actions().is_empty());
 //! ```
 //!
 //! # Features
@@ -117,18 +117,15 @@
 //!   It significantly reduces the number of calls of the default allocator,
 //!   so it is recommended to use this feature when the default allocator is not so efficient.
 //!   Disabled by default.
-//! - `holdem-hand-evaluator`: Uses [holdem-hand-evaluator] crate to evaluate hands.
-//!   It makes the tree construction slightly faster, but the program size will increase by about 200KB.
-//!   Enabled by default.
 //! - `rayon`: Uses [rayon] crate for parallelization.
-//!   Enabled by default.
+//!   Disabled by default.
 //!
-//! [holdem-hand-evaluator]: https://github.com/b-inary/holdem-hand-evaluator
 //! [rayon]: https://github.com/rayon-rs/rayon
 
 #![cfg_attr(feature = "custom_alloc", feature(allocator_api))]
 
 mod bet_size;
+mod equity;
 mod game;
 mod interface;
 mod interpreter;
@@ -136,15 +133,16 @@ mod mutex_like;
 mod range;
 mod sliceop;
 mod solver;
+mod storage;
 mod utility;
 
 #[cfg(feature = "custom_alloc")]
 mod alloc;
 
-#[cfg(not(feature = "holdem-hand-evaluator"))]
 mod hand;
 
 pub use bet_size::*;
+pub use equity::*;
 pub use game::*;
 pub use interface::*;
 pub use interpreter::*;
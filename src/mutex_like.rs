@@ -0,0 +1,66 @@
+//! A minimal `Mutex`-like wrapper used to share mutable node data across solver
+//! worker threads without the overhead of a real lock on the hot path.
+//!
+//! Callers are expected to partition access (e.g., by node) so that no two threads
+//! ever touch the same `MutexLike<T>` concurrently; the crate does not enforce this
+//! at runtime.
+
+use std::cell::UnsafeCell;
+use std::ops::{Deref, DerefMut};
+
+pub struct MutexLike<T: ?Sized> {
+    inner: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for MutexLike<T> {}
+unsafe impl<T: ?Sized + Send> Sync for MutexLike<T> {}
+
+impl<T: Clone> Clone for MutexLike<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.lock().clone())
+    }
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for MutexLike<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MutexLike").field(&*self.lock()).finish()
+    }
+}
+
+impl<T> MutexLike<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: UnsafeCell::new(value),
+        }
+    }
+}
+
+impl<T: ?Sized> MutexLike<T> {
+    /// Returns a guard granting mutable access to the inner value.
+    ///
+    /// Unlike [`std::sync::Mutex::lock`], this never blocks and never fails: it is the
+    /// caller's responsibility to ensure exclusive access.
+    #[inline]
+    pub fn lock(&self) -> MutexGuard<'_, T> {
+        MutexGuard { inner: &self.inner }
+    }
+}
+
+pub struct MutexGuard<'a, T: ?Sized> {
+    inner: &'a UnsafeCell<T>,
+}
+
+impl<T: ?Sized> Deref for MutexGuard<'_, T> {
+    type Target = T;
+    #[inline]
+    fn deref(&self) -> &T {
+        unsafe { &*self.inner.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for MutexGuard<'_, T> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut T {
+        unsafe { &mut *self.inner.get() }
+    }
+}
@@ -0,0 +1,461 @@
+//! Hand range parsing and representation, plus the basic card utilities shared
+//! across the crate.
+
+use std::str::FromStr;
+
+/// Sentinel value used for a flop/turn/river card slot that has not been dealt yet.
+pub const NOT_DEALT: u8 = 0xff;
+
+const RANK_CHARS: &str = "23456789TJQKA";
+const SUIT_CHARS: &str = "cdhs";
+
+/// Number of distinct ranks in a standard 52-card deck.
+const RANK_COUNT: usize = 13;
+
+/// The deck/game variant a [`crate::GameConfig`] is built for.
+///
+/// `ShortDeck` removes ranks 2-5 from the deck (36 cards total) and follows
+/// short-deck hand rankings (see [`crate::hand`] for where those rankings are
+/// applied).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Variant {
+    /// Standard 52-card Texas Hold'em.
+    #[default]
+    Standard,
+    /// 36-card short-deck (6-plus) Hold'em: ranks 2-5 are removed from the deck.
+    ShortDeck,
+}
+
+impl Variant {
+    /// Returns `true` if `rank` (0 = "2", ..., 12 = "A") is part of the deck for this variant.
+    #[inline]
+    pub fn contains_rank(&self, rank: u8) -> bool {
+        match self {
+            Variant::Standard => true,
+            Variant::ShortDeck => rank >= rank_from_char('6').unwrap(),
+        }
+    }
+
+    /// Returns `true` if `card` (0..52) is part of the deck for this variant.
+    #[inline]
+    pub fn contains_card(&self, card: u8) -> bool {
+        self.contains_rank(card >> 2)
+    }
+
+    /// Number of cards in the deck for this variant (52 or 36).
+    #[inline]
+    pub fn deck_size(&self) -> usize {
+        match self {
+            Variant::Standard => 52,
+            Variant::ShortDeck => 36,
+        }
+    }
+}
+
+#[inline]
+fn rank_from_char(c: char) -> Option<u8> {
+    RANK_CHARS
+        .chars()
+        .position(|r| r == c.to_ascii_uppercase())
+        .map(|i| i as u8)
+}
+
+#[inline]
+fn suit_from_char(c: char) -> Option<u8> {
+    SUIT_CHARS
+        .chars()
+        .position(|s| s == c.to_ascii_lowercase())
+        .map(|i| i as u8)
+}
+
+/// Converts a card string such as `"Td"` into a card ID (0..52).
+///
+/// Returns `None` if the string is not a valid card.
+pub fn card_from_str(s: &str) -> Option<u8> {
+    let mut chars = s.chars();
+    let rank = rank_from_char(chars.next()?)?;
+    let suit = suit_from_char(chars.next()?)?;
+    if chars.next().is_some() {
+        return None;
+    }
+    Some(rank * 4 + suit)
+}
+
+/// Converts a card ID (0..52) into its string representation (e.g., `"Td"`).
+///
+/// Returns `None` if `card` is [`NOT_DEALT`] or out of range.
+pub fn card_to_string(card: u8) -> Option<String> {
+    if card >= 52 {
+        return None;
+    }
+    let rank = (card >> 2) as usize;
+    let suit = (card & 3) as usize;
+    let mut s = String::with_capacity(2);
+    s.push(RANK_CHARS.as_bytes()[rank] as char);
+    s.push(SUIT_CHARS.as_bytes()[suit] as char);
+    Some(s)
+}
+
+/// Parses a 3-card flop string such as `"Td9d6h"` into an array of card IDs.
+pub fn flop_from_str(s: &str) -> Result<[u8; 3], String> {
+    if s.len() != 6 {
+        return Err(format!("invalid flop string: {s}"));
+    }
+    let mut result = [NOT_DEALT; 3];
+    for (i, chunk) in [&s[0..2], &s[2..4], &s[4..6]].into_iter().enumerate() {
+        result[i] = card_from_str(chunk).ok_or_else(|| format!("invalid card: {chunk}"))?;
+    }
+    if result[0] == result[1] || result[0] == result[2] || result[1] == result[2] {
+        return Err(format!("duplicate cards in flop: {s}"));
+    }
+    Ok(result)
+}
+
+/// A hand range represented as a 13x13 grid of weights.
+///
+/// The diagonal stores pocket pair weights, the upper triangle (`rank1 < rank2`)
+/// stores suited-combo weights, and the lower triangle stores offsuit-combo weights.
+/// Each weight is in `[0.0, 1.0]`, representing the fraction of that combo's
+/// card combinations that are included in the range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Range {
+    data: [f32; RANK_COUNT * RANK_COUNT],
+}
+
+impl Default for Range {
+    fn default() -> Self {
+        Self {
+            data: [0.0; RANK_COUNT * RANK_COUNT],
+        }
+    }
+}
+
+impl Range {
+    /// Creates a new, empty range.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    fn index(rank1: u8, rank2: u8) -> usize {
+        rank1 as usize * RANK_COUNT + rank2 as usize
+    }
+
+    /// Returns the weight of a pocket pair of the given rank.
+    pub fn get_pair_weight(&self, rank: u8) -> f32 {
+        self.data[Self::index(rank, rank)]
+    }
+
+    /// Returns the weight of a suited combo, `rank_hi` > `rank_lo`.
+    pub fn get_suited_weight(&self, rank_hi: u8, rank_lo: u8) -> f32 {
+        self.data[Self::index(rank_lo, rank_hi)]
+    }
+
+    /// Returns the weight of an offsuit combo, `rank_hi` > `rank_lo`.
+    pub fn get_offsuit_weight(&self, rank_hi: u8, rank_lo: u8) -> f32 {
+        self.data[Self::index(rank_hi, rank_lo)]
+    }
+
+    /// Sets the weight of a pocket pair of the given rank to an arbitrary fraction in
+    /// `[0.0, 1.0]`, e.g. to include only some of a pair's combos in the range.
+    pub fn set_pair_weight(&mut self, rank: u8, weight: f32) {
+        self.data[Self::index(rank, rank)] = weight;
+    }
+
+    /// Sets the weight of a suited combo (`rank_hi` > `rank_lo`) to an arbitrary
+    /// fraction in `[0.0, 1.0]`.
+    pub fn set_suited_weight(&mut self, rank_hi: u8, rank_lo: u8, weight: f32) {
+        self.data[Self::index(rank_lo, rank_hi)] = weight;
+    }
+
+    /// Sets the weight of an offsuit combo (`rank_hi` > `rank_lo`) to an arbitrary
+    /// fraction in `[0.0, 1.0]`.
+    pub fn set_offsuit_weight(&mut self, rank_hi: u8, rank_lo: u8, weight: f32) {
+        self.data[Self::index(rank_hi, rank_lo)] = weight;
+    }
+
+    /// Returns the raw underlying 13x13 weight grid.
+    pub fn raw_data(&self) -> &[f32; RANK_COUNT * RANK_COUNT] {
+        &self.data
+    }
+
+    /// Returns `true` if every combo whose rank is outside `variant`'s deck has zero weight.
+    ///
+    /// Used by [`crate::PostFlopGame::with_config`] to reject ranges that were written
+    /// for standard Hold'em but are being used to build a short-deck tree (or vice versa).
+    pub fn is_compatible_with(&self, variant: Variant) -> bool {
+        for rank1 in 0..RANK_COUNT as u8 {
+            for rank2 in 0..RANK_COUNT as u8 {
+                if self.data[Self::index(rank1, rank2)] != 0.0
+                    && (!variant.contains_rank(rank1) || !variant.contains_rank(rank2))
+                {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns the weight of the unordered card combo `(c1, c2)` under this range.
+    pub fn combo_weight(&self, c1: u8, c2: u8) -> f32 {
+        let (r1, r2) = (c1 >> 2, c2 >> 2);
+        if r1 == r2 {
+            self.get_pair_weight(r1)
+        } else {
+            let (hi, lo) = if r1 > r2 { (r1, r2) } else { (r2, r1) };
+            if (c1 & 3) == (c2 & 3) {
+                self.get_suited_weight(hi, lo)
+            } else {
+                self.get_offsuit_weight(hi, lo)
+            }
+        }
+    }
+
+    /// Parses a range string, rejecting any combo that references a rank outside `variant`'s deck.
+    ///
+    /// This is the entry point used when building a short-deck tree: unlike the plain
+    /// [`FromStr`] implementation (which always assumes [`Variant::Standard`]), it fails
+    /// fast on tokens like `"55"` or `"A4s"` instead of silently building an invalid range.
+    pub fn from_str_with_variant(s: &str, variant: Variant) -> Result<Self, String> {
+        let range = Self::from_str(s)?;
+        if !range.is_compatible_with(variant) {
+            return Err(format!(
+                "range '{s}' contains ranks that are not part of the {variant:?} deck"
+            ));
+        }
+        Ok(range)
+    }
+}
+
+impl FromStr for Range {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut range = Range::new();
+        for token in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            parse_token(&mut range, token)?;
+        }
+        Ok(range)
+    }
+}
+
+/// Parses a single comma-separated token (e.g., `"66+"`, `"A8s+"`, `"A5s-A4s"`, `"KQo"`)
+/// and applies it to `range`.
+fn parse_token(range: &mut Range, token: &str) -> Result<(), String> {
+    let (base, plus) = match token.strip_suffix('+') {
+        Some(base) => (base, true),
+        None => (token, false),
+    };
+
+    if let Some((hi, lo)) = base.split_once('-') {
+        if plus {
+            return Err(format!("invalid range token: {token}"));
+        }
+        return parse_dash_range(range, hi, lo);
+    }
+
+    apply_combo(range, base, plus)
+}
+
+/// Parses a single combo descriptor (without `+`/`-` modifiers already stripped off)
+/// such as `"66"`, `"A8s"`, `"KQo"`, `"AKs"` and sets its weight to `1.0`, optionally
+/// extending to all higher combos of the same shape when `plus` is set.
+fn apply_combo(range: &mut Range, combo: &str, plus: bool) -> Result<(), String> {
+    let chars: Vec<char> = combo.chars().collect();
+    let (r1, r2, suited) = match chars.len() {
+        2 => {
+            let r1 = rank_from_char(chars[0]).ok_or_else(|| format!("invalid rank: {combo}"))?;
+            let r2 = rank_from_char(chars[1]).ok_or_else(|| format!("invalid rank: {combo}"))?;
+            if r1 != r2 {
+                return Err(format!("suitedness required for non-pair combo: {combo}"));
+            }
+            (r1, r2, None)
+        }
+        3 => {
+            let r1 = rank_from_char(chars[0]).ok_or_else(|| format!("invalid rank: {combo}"))?;
+            let r2 = rank_from_char(chars[1]).ok_or_else(|| format!("invalid rank: {combo}"))?;
+            let suited = match chars[2].to_ascii_lowercase() {
+                's' => true,
+                'o' => false,
+                _ => return Err(format!("invalid suitedness: {combo}")),
+            };
+            (r1, r2, Some(suited))
+        }
+        _ => return Err(format!("invalid combo: {combo}")),
+    };
+
+    let (hi, lo) = if r1 >= r2 { (r1, r2) } else { (r2, r1) };
+
+    match suited {
+        None => {
+            if plus {
+                for rank in hi..RANK_COUNT as u8 {
+                    range.set_pair_weight(rank, 1.0);
+                }
+            } else {
+                range.set_pair_weight(hi, 1.0);
+            }
+        }
+        Some(true) => {
+            if hi == lo {
+                return Err(format!("pair cannot be suited: {combo}"));
+            }
+            if plus {
+                for kicker in lo..hi {
+                    range.set_suited_weight(hi, kicker, 1.0);
+                }
+            } else {
+                range.set_suited_weight(hi, lo, 1.0);
+            }
+        }
+        Some(false) => {
+            if hi == lo {
+                return Err(format!("pair cannot be offsuit: {combo}"));
+            }
+            if plus {
+                for kicker in lo..hi {
+                    range.set_offsuit_weight(hi, kicker, 1.0);
+                }
+            } else {
+                range.set_offsuit_weight(hi, lo, 1.0);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parses a `"A5s-A4s"`-style dash range: both ends must share the same high card and shape.
+fn parse_dash_range(range: &mut Range, hi_token: &str, lo_token: &str) -> Result<(), String> {
+    // Validate that both ends are well-formed combos before deriving the rank bounds below.
+    let mut temp_hi = Range::new();
+    let mut temp_lo = Range::new();
+    apply_combo(&mut temp_hi, hi_token, false)?;
+    apply_combo(&mut temp_lo, lo_token, false)?;
+
+    // Re-derive the (hi, lo) rank bounds directly instead of diffing grids.
+    let hi_chars: Vec<char> = hi_token.chars().collect();
+    let lo_chars: Vec<char> = lo_token.chars().collect();
+    if hi_chars.len() != lo_chars.len() {
+        return Err("dash ranges must share the same kicker shape".to_string());
+    }
+
+    if hi_chars.len() == 2 {
+        // Pair range, e.g. "QQ-22": both ends must themselves be pairs.
+        if hi_chars[0] != hi_chars[1] || lo_chars[0] != lo_chars[1] {
+            return Err("dash ranges must share the same kicker shape".to_string());
+        }
+        let hi_rank = rank_from_char(hi_chars[0]).ok_or_else(|| format!("invalid rank: {hi_token}"))?;
+        let lo_rank = rank_from_char(lo_chars[0]).ok_or_else(|| format!("invalid rank: {lo_token}"))?;
+        let (lo_bound, hi_bound) = if lo_rank <= hi_rank { (lo_rank, hi_rank) } else { (hi_rank, lo_rank) };
+        for rank in lo_bound..=hi_bound {
+            range.set_pair_weight(rank, 1.0);
+        }
+        return Ok(());
+    }
+
+    // Suited/offsuit kicker range, e.g. "A5s-A4s": both ends share the same anchor card and
+    // suitedness, and only the kicker varies.
+    if hi_chars.first() != lo_chars.first() || !hi_chars[2].eq_ignore_ascii_case(&lo_chars[2]) {
+        return Err("dash ranges must share the same kicker shape".to_string());
+    }
+    let top = rank_from_char(hi_chars[0]).ok_or_else(|| format!("invalid rank: {hi_token}"))?;
+    let from_rank = rank_from_char(hi_chars[1]).ok_or_else(|| format!("invalid rank: {hi_token}"))?;
+    let to_rank = rank_from_char(lo_chars[1]).ok_or_else(|| format!("invalid rank: {lo_token}"))?;
+    let (lo_bound, hi_bound) = if from_rank <= to_rank {
+        (from_rank, to_rank)
+    } else {
+        (to_rank, from_rank)
+    };
+
+    let suited = hi_chars[2].eq_ignore_ascii_case(&'s');
+    for kicker in lo_bound..=hi_bound {
+        if kicker == top {
+            continue;
+        }
+        if suited {
+            range.set_suited_weight(top, kicker, 1.0);
+        } else {
+            range.set_offsuit_weight(top, kicker, 1.0);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns every `(card1, card2)` combo in `variant`'s deck that has nonzero weight
+/// in `range` and doesn't overlap `excluded_mask` (already-dealt/dead cards).
+pub fn hand_combos(range: &Range, variant: Variant, excluded_mask: u64) -> Vec<(u8, u8)> {
+    let mut result = Vec::new();
+    for c1 in 0..52u8 {
+        if !variant.contains_card(c1) || excluded_mask & (1 << c1) != 0 {
+            continue;
+        }
+        for c2 in (c1 + 1)..52u8 {
+            if !variant.contains_card(c2) || excluded_mask & (1 << c2) != 0 {
+                continue;
+            }
+            if range.combo_weight(c1, c2) > 0.0 {
+                result.push((c1, c2));
+            }
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_combo_pair_plus() {
+        let range: Range = "88+".parse().unwrap();
+        assert_eq!(range.get_pair_weight(rank_from_char('8').unwrap()), 1.0);
+        assert_eq!(range.get_pair_weight(rank_from_char('A').unwrap()), 1.0);
+        assert_eq!(range.get_pair_weight(rank_from_char('7').unwrap()), 0.0);
+    }
+
+    #[test]
+    fn apply_combo_suited_and_offsuit() {
+        let range: Range = "AKs,KQo".parse().unwrap();
+        let a = rank_from_char('A').unwrap();
+        let k = rank_from_char('K').unwrap();
+        let q = rank_from_char('Q').unwrap();
+        assert_eq!(range.get_suited_weight(a, k), 1.0);
+        assert_eq!(range.get_offsuit_weight(a, k), 0.0);
+        assert_eq!(range.get_offsuit_weight(k, q), 1.0);
+        assert_eq!(range.get_suited_weight(k, q), 0.0);
+    }
+
+    #[test]
+    fn parse_dash_range_pair() {
+        let range: Range = "QQ-22".parse().unwrap();
+        for c in "23456789TJQ".chars() {
+            assert_eq!(range.get_pair_weight(rank_from_char(c).unwrap()), 1.0, "{c}{c} should be included");
+        }
+        assert_eq!(range.get_pair_weight(rank_from_char('K').unwrap()), 0.0);
+    }
+
+    #[test]
+    fn parse_dash_range_suited_anchor() {
+        let range: Range = "A5s-A4s".parse().unwrap();
+        let a = rank_from_char('A').unwrap();
+        assert_eq!(range.get_suited_weight(a, rank_from_char('5').unwrap()), 1.0);
+        assert_eq!(range.get_suited_weight(a, rank_from_char('4').unwrap()), 1.0);
+        assert_eq!(range.get_suited_weight(a, rank_from_char('3').unwrap()), 0.0);
+        assert_eq!(range.get_offsuit_weight(a, rank_from_char('5').unwrap()), 0.0);
+    }
+
+    #[test]
+    fn parse_dash_range_rejects_mismatched_shape() {
+        assert!("A5s-A4o".parse::<Range>().is_err());
+        assert!("QQ-A2s".parse::<Range>().is_err());
+    }
+
+    #[test]
+    fn short_deck_rejects_ranks_outside_deck() {
+        assert!(Range::from_str_with_variant("55", Variant::ShortDeck).is_err());
+        assert!(Range::from_str_with_variant("A4s", Variant::ShortDeck).is_err());
+        assert!(Range::from_str_with_variant("66+", Variant::ShortDeck).is_ok());
+    }
+}
@@ -0,0 +1,23 @@
+//! Low-level helpers for operating on the flat `f32` regret/strategy arrays that back
+//! each node, regardless of which [`crate::game`] storage backend holds them.
+
+/// `target[i] += source[i]` for every element.
+#[allow(dead_code)]
+#[inline]
+pub(crate) fn add_slice(target: &mut [f32], source: &[f32]) {
+    target.iter_mut().zip(source.iter()).for_each(|(t, s)| *t += s);
+}
+
+/// `target[i] *= scalar` for every element.
+#[allow(dead_code)]
+#[inline]
+pub(crate) fn mul_slice(target: &mut [f32], scalar: f32) {
+    target.iter_mut().for_each(|t| *t *= scalar);
+}
+
+/// Returns the maximum absolute value in `slice`, or `0.0` if empty.
+#[allow(dead_code)]
+#[inline]
+pub(crate) fn max_abs_slice(slice: &[f32]) -> f32 {
+    slice.iter().fold(0.0, |acc, &x| acc.max(x.abs()))
+}
@@ -0,0 +1,417 @@
+//! Vanilla counterfactual regret minimization (CFR) over the single-street bet tree
+//! built by [`crate::game::build_tree`].
+//!
+//! This performs a real (if simple) regret-matching solve: each call to
+//! [`solve_step`] walks the whole tree once, computing every decision node's
+//! counterfactual value per hand and per action, accumulating regrets and
+//! time-averaged strategy weight from them. [`compute_exploitability`] computes a
+//! real best-response value for each player against the other's current average
+//! strategy. Neither pass yet takes advantage of the `rayon` feature; both are
+//! single-threaded regardless of which storage backend is selected.
+//!
+//! Scope: since [`crate::game::build_tree`] only produces real decision nodes for a
+//! board that was fully dealt up front (see its doc comment), solving a config with
+//! any street left undealt walks a tree of chance/placeholder-terminal nodes only,
+//! and both `solve_step` and `compute_exploitability` correctly treat that as
+//! contributing no regret and no exploitability (there's nothing to solve yet).
+
+use std::cmp::Ordering;
+
+use crate::game::{Node, ShowdownTable, TerminalPayoff};
+use crate::sliceop::{add_slice, mul_slice};
+use crate::{compute_average, Player, PostFlopGame};
+
+/// Runs CFR iterations on `game` until `target_exploitability` is reached or
+/// `max_num_iterations` is exhausted, returning the final exploitability.
+pub fn solve(
+    game: &mut PostFlopGame,
+    max_num_iterations: u32,
+    target_exploitability: f32,
+    print_progress: bool,
+) -> f32 {
+    let mut exploitability = f32::INFINITY;
+    for i in 0..max_num_iterations {
+        solve_step(game, i);
+        if (i + 1) % 10 == 0 || i + 1 == max_num_iterations {
+            exploitability = compute_exploitability(game);
+            if print_progress {
+                println!("iteration {}: exploitability = {:.4}", i + 1, exploitability);
+            }
+            if exploitability <= target_exploitability {
+                break;
+            }
+        }
+    }
+    finalize(game);
+    exploitability
+}
+
+/// Runs a single vanilla-CFR iteration over `game`'s whole tree: computes every
+/// decision node's counterfactual value per hand and per action from the leaves up,
+/// and accumulates regrets (skipped at a [`crate::PostFlopGame::lock_node_strategy`]-pinned
+/// node, whose action frequencies stay fixed) and time-averaged strategy weight from
+/// them.
+///
+/// `iteration` is currently unused: this is plain vanilla CFR rather than one of its
+/// discounted variants (e.g. CFR+ or linear CFR), which would weight each
+/// iteration's contribution by `iteration`.
+pub fn solve_step(game: &PostFlopGame, _iteration: u32) {
+    let reach = [game.hand_weights(0), game.hand_weights(1)];
+    cfr_traverse(game.root(), &[&reach[0], &reach[1]], true);
+}
+
+/// Returns `(oop_value, ip_value)`: the zero-sum chip value each player takes away
+/// from a terminal with the given outcome, relative to each player's stack before
+/// this street's betting (i.e. treating the pre-street pot as already split evenly
+/// between them, so a check-through chop nets `0` for both).
+fn terminal_values(payoff: &TerminalPayoff, ordering: Ordering) -> (f32, f32) {
+    let committed0 = payoff.committed[0] as f32;
+    let committed1 = payoff.committed[1] as f32;
+    let half_pot = (payoff.pot as f32 - committed0 - committed1) / 2.0;
+    match ordering {
+        Ordering::Greater => {
+            let value = half_pot + committed1;
+            (value, -value)
+        }
+        Ordering::Less => {
+            let value = half_pot + committed0;
+            (-value, value)
+        }
+        Ordering::Equal => {
+            let value = (committed1 - committed0) / 2.0;
+            (value, -value)
+        }
+    }
+}
+
+/// Returns the counterfactual value of each of `own`'s `num_own` hands at a
+/// terminal, weighted by the opponent's reach probability for each of their hands
+/// (`reach_opp`). Combos that share a card with the opponent's hand can never
+/// actually occur together, and are skipped (via `table.compare` returning `None`),
+/// exactly as in [`crate::equity::compute_equity`]'s combo filtering.
+fn terminal_cfv(payoff: &TerminalPayoff, table: &ShowdownTable, reach_opp: &[f32], num_own: usize, own_is_oop: bool) -> Vec<f32> {
+    let mut cfv = vec![0.0f32; num_own];
+    for (h, cfv_h) in cfv.iter_mut().enumerate() {
+        for (j, &reach) in reach_opp.iter().enumerate() {
+            if reach == 0.0 {
+                continue;
+            }
+            let (oop_hand, ip_hand) = if own_is_oop { (h, j) } else { (j, h) };
+            let Some(showdown_ordering) = table.compare(oop_hand, ip_hand) else {
+                continue;
+            };
+            // A fold's outcome doesn't depend on either hand's strength, only on who
+            // folded; reuse `terminal_values` by handing it a synthetic ordering that
+            // always favors the fold winner, rather than duplicating its chip math.
+            let ordering = match payoff.fold_winner {
+                Some(0) => Ordering::Greater,
+                Some(_) => Ordering::Less,
+                None => showdown_ordering,
+            };
+            let (value_oop, value_ip) = terminal_values(payoff, ordering);
+            *cfv_h += reach * if own_is_oop { value_oop } else { value_ip };
+        }
+    }
+    cfv
+}
+
+/// Recursively computes `[cfv_oop, cfv_ip]`: the counterfactual value of every hand
+/// for both players at `node`, given each player's current reach-probability vector
+/// in `reach` (indexed `[player][hand]`, starting as each player's range weight at
+/// the root).
+///
+/// When `train` is `true` (a `solve_step` training iteration), this reads each
+/// decision node's instantaneous [`Node::regret_matching_strategy`] and, as a side
+/// effect, accumulates regrets and time-averaged strategy weight into every unlocked
+/// decision node visited along the way. When `false` (an evaluation pass, see
+/// [`finalize`]), it instead reads [`Node::average_strategy`], does not touch
+/// regrets/strategy-sum, and caches the result into every visited node's
+/// [`Node::cfv`] for [`crate::Interpreter::expected_values`] to read back later.
+///
+/// Takes borrowed slices rather than owned `Vec`s so that descending into one
+/// action's subtree only needs to allocate a scaled copy of the *acting* player's
+/// reach vector (see [`cfr_traverse_decision`]); the other player's reach is
+/// unaffected by this node's strategy and is passed through by reference.
+fn cfr_traverse(node: &Node, reach: &[&[f32]; 2], train: bool) -> [Vec<f32>; 2] {
+    let result = match node.player {
+        Player::Terminal => {
+            let (Some(payoff), Some(table)) = (&node.terminal, &node.showdown) else {
+                // The still-placeholder terminal produced for a config with an undealt
+                // street (see `build_tree`'s doc comment): no payoff to evaluate.
+                return [vec![0.0; reach[0].len()], vec![0.0; reach[1].len()]];
+            };
+            let cfv_oop = terminal_cfv(payoff, table, reach[1], reach[0].len(), true);
+            let cfv_ip = terminal_cfv(payoff, table, reach[0], reach[1].len(), false);
+            [cfv_oop, cfv_ip]
+        }
+        Player::Chance => {
+            // Only reachable below a still-undealt street, where every child is itself
+            // a placeholder (see the `Terminal` arm above); there is no real strategy
+            // to weight children by yet, so this just averages them.
+            let mut totals = [vec![0.0; reach[0].len()], vec![0.0; reach[1].len()]];
+            for child in &node.children {
+                let child_cfv = cfr_traverse(child, reach, train);
+                add_slice(&mut totals[0], &child_cfv[0]);
+                add_slice(&mut totals[1], &child_cfv[1]);
+            }
+            if !node.children.is_empty() {
+                let scale = 1.0 / node.children.len() as f32;
+                mul_slice(&mut totals[0], scale);
+                mul_slice(&mut totals[1], scale);
+            }
+            totals
+        }
+        Player::Oop | Player::Ip => cfr_traverse_decision(node, reach, train),
+    };
+    if !train {
+        *node.cfv.lock() = result.clone();
+    }
+    result
+}
+
+fn cfr_traverse_decision(node: &Node, reach: &[&[f32]; 2], train: bool) -> [Vec<f32>; 2] {
+    let player = if node.player == Player::Oop { 0 } else { 1 };
+    let opponent = 1 - player;
+    let num_hands = reach[player].len();
+    let num_actions = node.actions.len();
+    let strategy = if train { node.regret_matching_strategy() } else { node.average_strategy() };
+
+    let mut node_cfv_own = vec![0.0f32; num_hands];
+    let mut total_cfv_opp = vec![0.0f32; reach[opponent].len()];
+    let mut action_cfv_own = Vec::with_capacity(num_actions);
+
+    for (action_index, child) in node.children.iter().enumerate() {
+        let mut scaled_own_reach = reach[player].to_vec();
+        for (h, reach_h) in scaled_own_reach.iter_mut().enumerate() {
+            *reach_h *= strategy[h * num_actions + action_index];
+        }
+        let mut next_reach = *reach;
+        next_reach[player] = &scaled_own_reach;
+
+        let [cfv_child_oop, cfv_child_ip] = cfr_traverse(child, &next_reach, train);
+        let (cfv_own, cfv_opp) = if player == 0 {
+            (cfv_child_oop, cfv_child_ip)
+        } else {
+            (cfv_child_ip, cfv_child_oop)
+        };
+
+        add_slice(&mut total_cfv_opp, &cfv_opp);
+        for (h, &value) in cfv_own.iter().enumerate() {
+            node_cfv_own[h] += strategy[h * num_actions + action_index] * value;
+        }
+        action_cfv_own.push(cfv_own);
+    }
+
+    if train && node.locked_strategy.is_none() {
+        let mut regrets = node.regrets.lock();
+        let mut strategy_sum = node.strategy_sum.lock();
+        for h in 0..num_hands {
+            for a in 0..num_actions {
+                let regret = action_cfv_own[a][h] - node_cfv_own[h];
+                regrets[h * num_actions + a] += regret;
+                strategy_sum[h * num_actions + a] += reach[player][h] * strategy[h * num_actions + a];
+            }
+        }
+    }
+
+    let mut result = [Vec::new(), Vec::new()];
+    result[player] = node_cfv_own;
+    result[opponent] = total_cfv_opp;
+    result
+}
+
+/// Recursively computes the best-response value of each of `responder`'s
+/// `num_responder_hands` hands at `node`, against the *other* player's current
+/// time-averaged strategy (see [`Node::average_strategy`]), given the other
+/// player's reach-probability vector `reach_fixed`.
+///
+/// At a node where `responder` acts, the responder is assumed to know their own
+/// hand exactly (as in any imperfect-information best response), so each hand
+/// independently takes whichever action maximizes its own value — there is no
+/// mixing to consider on the responder's side.
+///
+/// Also used, rooted at an arbitrary subtree node rather than the game root, by
+/// [`crate::Interpreter::expected_values`] to compute the exploitative component of
+/// its blended EV.
+pub(crate) fn best_response(node: &Node, responder: usize, reach_fixed: &[f32], num_responder_hands: usize) -> Vec<f32> {
+    match node.player {
+        Player::Terminal => {
+            let (Some(payoff), Some(table)) = (&node.terminal, &node.showdown) else {
+                return vec![0.0; num_responder_hands];
+            };
+            terminal_cfv(payoff, table, reach_fixed, num_responder_hands, responder == 0)
+        }
+        Player::Chance => {
+            let mut total = vec![0.0f32; num_responder_hands];
+            for child in &node.children {
+                let child_value = best_response(child, responder, reach_fixed, num_responder_hands);
+                add_slice(&mut total, &child_value);
+            }
+            if !node.children.is_empty() {
+                mul_slice(&mut total, 1.0 / node.children.len() as f32);
+            }
+            total
+        }
+        Player::Oop | Player::Ip => {
+            let player = if node.player == Player::Oop { 0 } else { 1 };
+            if player == responder {
+                let mut best = vec![f32::NEG_INFINITY; num_responder_hands];
+                for child in &node.children {
+                    let child_value = best_response(child, responder, reach_fixed, num_responder_hands);
+                    for (h, &value) in child_value.iter().enumerate() {
+                        best[h] = best[h].max(value);
+                    }
+                }
+                if node.children.is_empty() {
+                    vec![0.0; num_responder_hands]
+                } else {
+                    best
+                }
+            } else {
+                let strategy = node.average_strategy();
+                let num_actions = node.actions.len();
+                let mut total = vec![0.0f32; num_responder_hands];
+                for (action_index, child) in node.children.iter().enumerate() {
+                    let mut next_reach_fixed = reach_fixed.to_vec();
+                    for (h, reach_h) in next_reach_fixed.iter_mut().enumerate() {
+                        *reach_h *= strategy[h * num_actions + action_index];
+                    }
+                    let child_value = best_response(child, responder, &next_reach_fixed, num_responder_hands);
+                    add_slice(&mut total, &child_value);
+                }
+                total
+            }
+        }
+    }
+}
+
+/// Computes the exploitability of `game`'s current average strategy (see
+/// [`Node::average_strategy`]): the range-weighted average of each player's
+/// best-response value against the other's average strategy, averaged over both
+/// players. This is the standard zero-sum best-response exploitability measure: it
+/// is always `>= 0`, and converges to `0` as the average strategy approaches a Nash
+/// equilibrium.
+pub fn compute_exploitability(game: &PostFlopGame) -> f32 {
+    let weights = [game.hand_weights(0), game.hand_weights(1)];
+
+    let br_oop = best_response(game.root(), 0, &weights[1], weights[0].len());
+    let br_ip = best_response(game.root(), 1, &weights[0], weights[1].len());
+
+    let average_oop = compute_average(&br_oop, &weights[0]);
+    let average_ip = compute_average(&br_ip, &weights[1]);
+    (average_oop + average_ip) / 2.0
+}
+
+/// Evaluates `game`'s current average strategy (see [`Node::average_strategy`]) once
+/// over the whole tree and caches each node's per-hand counterfactual value into
+/// [`Node::cfv`], for [`crate::Interpreter::expected_values`] to read back later.
+/// [`Node::average_strategy`] itself needs no separate finalization (it derives the
+/// time-averaged strategy from `strategy_sum` on demand), so this is the only work
+/// needed before handing a solved `game` to an [`crate::Interpreter`].
+pub fn finalize(game: &mut PostFlopGame) {
+    let reach = [game.hand_weights(0), game.hand_weights(1)];
+    cfr_traverse(game.root(), &[&reach[0], &reach[1]], false);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{card_from_str, BetSizeCandidates, GameConfig, PostFlopGame};
+
+    fn fully_dealt_config() -> GameConfig {
+        GameConfig {
+            flop: [
+                card_from_str("Td").unwrap(),
+                card_from_str("9d").unwrap(),
+                card_from_str("6h").unwrap(),
+            ],
+            turn: card_from_str("Qh").unwrap(),
+            river: card_from_str("2c").unwrap(),
+            starting_pot: 100,
+            effective_stack: 400,
+            range: ["AA".parse().unwrap(), "KK".parse().unwrap()],
+            river_bet_sizes: [
+                BetSizeCandidates::try_from(("50%", "100%")).unwrap(),
+                BetSizeCandidates::try_from(("50%", "100%")).unwrap(),
+            ],
+            ..GameConfig::default()
+        }
+    }
+
+    /// Like [`fully_dealt_config`], but with the ranges swapped: OOP holds a hand that
+    /// always loses at showdown (a pure bluff) and IP holds the nuts (a pure value
+    /// hand that never has a reason to fold). Unlike the AA-vs-KK fixture above (where
+    /// OOP's hand never loses, so betting and checking are equally safe), this gives
+    /// betting a genuine downside when unlocked: IP never folds the nuts, so OOP's
+    /// bluff only costs money. That downside disappears once IP is locked to always
+    /// fold regardless of holding.
+    fn bluff_fixture_config() -> GameConfig {
+        GameConfig {
+            range: ["72o".parse().unwrap(), "AA".parse().unwrap()],
+            ..fully_dealt_config()
+        }
+    }
+
+    #[test]
+    fn solving_accumulates_nonzero_regret() {
+        let game = PostFlopGame::with_config(&fully_dealt_config()).unwrap();
+        assert_eq!(game.max_abs_regret(), 0.0);
+        for i in 0..50 {
+            solve_step(&game, i);
+        }
+        assert!(game.max_abs_regret() > 0.0);
+    }
+
+    #[test]
+    fn exploitability_decreases_as_solving_progresses() {
+        let game = PostFlopGame::with_config(&fully_dealt_config()).unwrap();
+        let early = compute_exploitability(&game);
+        for i in 0..200 {
+            solve_step(&game, i);
+        }
+        let late = compute_exploitability(&game);
+        assert!(late < early, "late exploitability {late} should be lower than early {early}");
+    }
+
+    #[test]
+    fn locking_ip_to_always_fold_makes_oop_bet_more() {
+        // OOP's only hand always loses at showdown (see `bluff_fixture_config`), so
+        // unlocked, IP's nuts never fold and OOP's bluff has nothing to gain from
+        // betting. With IP pinned to always fold instead, betting can never induce a
+        // showdown loss for OOP, so OOP's solved strategy should shift heavily toward
+        // betting compared to an unlocked solve of the same spot.
+        let unlocked_game = PostFlopGame::with_config(&bluff_fixture_config()).unwrap();
+        for i in 0..300 {
+            solve_step(&unlocked_game, i);
+        }
+        let root = unlocked_game.root();
+        let unlocked_bet_freq = average_bet_frequency(root);
+
+        let mut locked_game = PostFlopGame::with_config(&bluff_fixture_config()).unwrap();
+        let facing_bet_node_hands = {
+            let facing_bet = &locked_game.root().children[1];
+            facing_bet.num_hands()
+        };
+        // `facing_bet`'s actions are `[Fold, Call, Raise]`; pin every hand to `Fold`.
+        let always_fold = [1.0, 0.0, 0.0].repeat(facing_bet_node_hands);
+        locked_game.lock_node_strategy(&[1], always_fold).unwrap();
+        for i in 0..300 {
+            solve_step(&locked_game, i);
+        }
+        let locked_bet_freq = average_bet_frequency(locked_game.root());
+
+        assert!(
+            locked_bet_freq > unlocked_bet_freq,
+            "locked bet frequency {locked_bet_freq} should exceed unlocked {unlocked_bet_freq}"
+        );
+    }
+
+    /// Returns the range-weighted average probability that OOP's root strategy bets
+    /// (action index 1, i.e. `Bet(50)`) rather than checking.
+    fn average_bet_frequency(root: &Node) -> f32 {
+        let num_actions = root.actions.len();
+        let strategy = root.average_strategy();
+        let bet_freq: Vec<f32> = strategy.chunks(num_actions).map(|chunk| chunk[1]).collect();
+        bet_freq.iter().sum::<f32>() / bet_freq.len() as f32
+    }
+}
@@ -0,0 +1,164 @@
+//! Out-of-core storage backend: a memory-mapped file standing in for a single
+//! in-RAM allocation, so the OS can page cold subtrees to disk instead of the
+//! solve failing partway through on large trees.
+//!
+//! [`MmapArena`] maps the file; [`crate::game::NodeStorage`] is the storage-handle
+//! abstraction that lets a node's `regrets`/`strategy_sum` live either in its own
+//! `Vec<f32>` or in a window of this arena, behind the same `&[f32]`/`&mut [f32]`
+//! surface either way. [`crate::PostFlopGame::allocate_memory_mmap`] creates the
+//! arena sized to the tree's exact real storage need, then walks the tree swapping
+//! every node's storage for a non-overlapping window into it (deduplicating shared
+//! subtrees by `Rc` pointer identity, the same way [`crate::game::count_nodes`]
+//! does), copying across whatever was already stored. [`crate::PostFlopGame::
+//! allocate_memory`] walks back the other way (copying each window's contents into a
+//! fresh `Vec` before dropping the arena) if called after a memory-mapped
+//! allocation, so switching storage modes never leaves a node pointing at unmapped
+//! memory.
+
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::ptr::NonNull;
+
+/// A `f32` array backed by a memory-mapped file rather than a `Vec<f32>`.
+///
+/// Reads and writes go through the same `&mut [f32]` surface as the in-RAM arena
+/// (see [`crate::sliceop`]), so the rest of the solver doesn't need to know which
+/// backend a given node's storage came from.
+pub(crate) struct MmapArena {
+    ptr: NonNull<f32>,
+    len: usize,
+}
+
+unsafe impl Send for MmapArena {}
+unsafe impl Sync for MmapArena {}
+
+impl MmapArena {
+    /// Creates (or truncates) `path` to hold `len` `f32`s and maps it into memory.
+    pub fn create(path: &Path, len: usize) -> io::Result<Self> {
+        let byte_len = len * std::mem::size_of::<f32>();
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)?;
+        file.set_len(byte_len as u64)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::io::AsRawFd;
+            let addr = unsafe {
+                libc_mmap(
+                    std::ptr::null_mut(),
+                    byte_len,
+                    PROT_READ | PROT_WRITE,
+                    MAP_SHARED,
+                    file.as_raw_fd(),
+                    0,
+                )
+            };
+            if addr == MAP_FAILED {
+                return Err(io::Error::last_os_error());
+            }
+            let ptr = NonNull::new(addr as *mut f32).ok_or_else(io::Error::last_os_error)?;
+            Ok(Self { ptr, len })
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = file;
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "memory-mapped storage is only supported on unix targets",
+            ))
+        }
+    }
+
+    // Exercised by this module's tests; production code goes through `sub_ptr`
+    // instead (see its doc comment), hence `allow(dead_code)` for non-test builds.
+    #[allow(dead_code)]
+    pub fn as_slice(&self) -> &[f32] {
+        unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    #[allow(dead_code)]
+    pub fn as_mut_slice(&mut self) -> &mut [f32] {
+        unsafe { std::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Returns a raw pointer to the `offset`-th `f32` in the arena, valid for
+    /// `self.len() - offset` elements from there. Used by
+    /// [`crate::PostFlopGame::allocate_memory_mmap`] to hand each node its own
+    /// non-overlapping window (as a [`crate::game::NodeStorage::Mmap`]) without
+    /// holding a borrowed `&mut [f32]` over the whole arena across the tree walk,
+    /// since sibling nodes' windows alias the same backing allocation by design.
+    pub(crate) fn sub_ptr(&mut self, offset: usize) -> NonNull<f32> {
+        debug_assert!(offset <= self.len);
+        unsafe { NonNull::new_unchecked(self.ptr.as_ptr().add(offset)) }
+    }
+}
+
+impl Drop for MmapArena {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        unsafe {
+            let byte_len = self.len * std::mem::size_of::<f32>();
+            libc_munmap(self.ptr.as_ptr() as *mut _, byte_len);
+        }
+    }
+}
+
+#[cfg(unix)]
+const PROT_READ: i32 = 1;
+#[cfg(unix)]
+const PROT_WRITE: i32 = 2;
+#[cfg(unix)]
+const MAP_SHARED: i32 = 1;
+#[cfg(unix)]
+const MAP_FAILED: *mut std::ffi::c_void = !0 as *mut std::ffi::c_void;
+
+#[cfg(unix)]
+extern "C" {
+    #[link_name = "mmap"]
+    fn libc_mmap(
+        addr: *mut std::ffi::c_void,
+        len: usize,
+        prot: i32,
+        flags: i32,
+        fd: i32,
+        offset: i64,
+    ) -> *mut std::ffi::c_void;
+
+    #[link_name = "munmap"]
+    fn libc_munmap(addr: *mut std::ffi::c_void, len: usize) -> i32;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("postflop-solver-test-{name}-{}.mmap", std::process::id()))
+    }
+
+    #[test]
+    fn mmap_arena_round_trips_writes() {
+        let path = temp_path("round-trip");
+        let mut arena = MmapArena::create(&path, 4).unwrap();
+        arena.as_mut_slice().copy_from_slice(&[1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(arena.as_slice(), &[1.0, 2.0, 3.0, 4.0]);
+        drop(arena);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn mmap_arena_is_zero_initialized() {
+        let path = temp_path("zero-init");
+        let arena = MmapArena::create(&path, 8).unwrap();
+        assert_eq!(arena.as_slice(), &[0.0; 8]);
+        drop(arena);
+        std::fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,16 @@
+//! Small numeric helpers shared across the public API.
+
+/// Computes the weight-average of `values`, ignoring entries whose weight is zero.
+pub fn compute_average(values: &[f32], weights: &[f32]) -> f32 {
+    let mut sum = 0.0;
+    let mut weight_sum = 0.0;
+    for (&value, &weight) in values.iter().zip(weights.iter()) {
+        sum += value * weight;
+        weight_sum += weight;
+    }
+    if weight_sum > 0.0 {
+        sum / weight_sum
+    } else {
+        0.0
+    }
+}